@@ -0,0 +1,21 @@
+import os
+
+# HTTP timeouts in seconds for requests to the Polygon API
+CONNECT_TIMEOUT = float(os.environ.get('POLYGON_CONNECT_TIMEOUT', 5))
+READ_TIMEOUT = float(os.environ.get('POLYGON_READ_TIMEOUT', 30))
+
+# Retry settings: exponential backoff with full jitter, capped at BACKOFF_MAX seconds
+MAX_RETRIES = int(os.environ.get('POLYGON_MAX_RETRIES', 5))
+BACKOFF_BASE = float(os.environ.get('POLYGON_BACKOFF_BASE', 1))
+BACKOFF_MAX = float(os.environ.get('POLYGON_BACKOFF_MAX', 60))
+
+# Client-side rate limit shared by all requests (the free tier allows 5 requests per minute, 0 disables the limiter)
+RATE_LIMIT_REQUESTS = int(os.environ.get('POLYGON_RATE_LIMIT_REQUESTS', 5))
+RATE_LIMIT_PERIOD = float(os.environ.get('POLYGON_RATE_LIMIT_PERIOD', 60))
+
+# Base URL of the Polygon REST API (point it at a mock server for local development and tests)
+BASE_URL = os.environ.get('POLYGON_BASE_URL', 'https://api.polygon.io')
+
+# Base URL of the Polygon WebSocket feed, below which each market has its cluster (/stocks, /forex, /crypto, /indices)
+# Use wss://delayed.polygon.io for 15-minute delayed data, or the URL of a local replay server (tests/replay_polygon.py)
+STREAM_URL = os.environ.get('POLYGON_STREAM_URL', 'wss://socket.polygon.io')
@@ -0,0 +1,36 @@
+import os
+
+# Location of the persistent response cache (SQLite)
+root_dir_path = os.path.abspath(os.path.join(os.path.dirname(__file__), '..'))
+CACHE_PATH = os.environ.get('POLYGON_CACHE_PATH', os.path.join(root_dir_path, '.cache', 'polygon.sqlite'))
+
+# Serve every request from the cache and never touch the network
+OFFLINE_MODE = os.environ.get('POLYGON_OFFLINE', '').lower() in ('1', 'true', 'yes')
+
+# Time to live in seconds per endpoint (None caches forever, 0 disables caching)
+# Single second to daily bars are stored separately and only complete days are kept, so they never expire
+# (split-adjusted bars are dropped once the ticker splits after they were stored);
+# 'aggs' applies to weekly and longer bars, to multiples such as 5-minute bars and to sorted or limited queries, which are cached as whole responses
+CACHE_TTL = {
+    'aggs': 60 * 60,
+    'indicators': 60 * 60,
+    'prev_close': 60 * 60,
+    'open_close': 24 * 60 * 60,
+    'grouped_daily': 24 * 60 * 60,
+    'snapshot': 5,
+    'conversion': 5,
+    'exchanges': 7 * 24 * 60 * 60,
+    'conditions': 7 * 24 * 60 * 60,
+    'market_status': 60,
+    'market_holidays': 24 * 60 * 60,
+    'options_contracts': 24 * 60 * 60,
+    'option_chain': 60,
+    'ticker_search': 24 * 60 * 60,
+    'ticker_universe': 7 * 24 * 60 * 60,
+    'ticker_details': 24 * 60 * 60,
+    'related_companies': 7 * 24 * 60 * 60,
+    'financials': 24 * 60 * 60,
+    'splits': 24 * 60 * 60,
+    'dividends': 24 * 60 * 60,
+    'news': 5 * 60,
+}
@@ -1,10 +1,89 @@
+import os
 import streamlit as st
+import pandas as pd
+
+# Thousands and decimal separators for each supported display locale
+LOCALE_SEPARATORS = {
+    'en_US': (',', '.'),
+    'ja_JP': (',', '.'),
+    'de_DE': ('.', ','),
+    'fr_FR': (' ', ','),
+}
+
+# Locale used to format numbers at render time
+DISPLAY_LOCALE = os.environ.get('DISPLAY_LOCALE', 'en_US')
+
+# Number of decimals shown for numeric columns (columns not listed use DEFAULT_DECIMALS)
+DEFAULT_DECIMALS = 2
+COLUMN_DECIMALS = {
+    'Volume': 0,
+    'Transactions': 0,
+    'Split From': 0,
+    'Split To': 0,
+    'Adjustment Factor': 10,
+    'Size': 0,
+    'Bid Size': 0,
+    'Ask Size': 0,
+    'Sequence Number': 0,
+}
+
+# Options chain columns, also shown side by side as 'Call ...' and 'Put ...'
+OPTION_COLUMN_DECIMALS = {'IV': 4, 'Delta': 4, 'Gamma': 4, 'Theta': 4, 'Vega': 4, 'Open Interest': 0, 'Volume': 0}
+COLUMN_DECIMALS.update({f"{side}{col}": decimals for side in ('', 'Call ', 'Put ') for col, decimals in OPTION_COLUMN_DECIMALS.items()})
+
+# Largest DataFrame that is formatted with a pandas Styler (Streamlit refuses to render bigger ones)
+MAX_STYLED_CELLS = 262144
+
+# Format a number with the separators of the display locale
+def format_number(value, decimals=DEFAULT_DECIMALS, locale=DISPLAY_LOCALE):
+    if pd.isna(value):
+        return ''
+    thousands, decimal = LOCALE_SEPARATORS.get(locale, LOCALE_SEPARATORS['en_US'])
+    text = f"{value:,.{decimals}f}"
+    return text.replace(',', '\0').replace('.', decimal).replace('\0', thousands)
+
+# Build Streamlit column configs for the date and numeric columns of a DataFrame
+def build_column_config(df, styled=True):
+    column_config = {}
+    columns = [(col, df[col]) for col in df.columns]
+    if isinstance(df.index, pd.DatetimeIndex):
+        columns.append(('_index', df.index.to_series()))
+    for col, values in columns:
+        if pd.api.types.is_datetime64_any_dtype(values):
+            # Show the time only when the values are intraday, seconds only for second bars and milliseconds only for ticks
+            values = values.dropna()
+            if (values.dt.microsecond != 0).any():
+                datetime_format = 'YYYY-MM-DD HH:mm:ss.SSS'
+            elif (values.dt.second != 0).any():
+                datetime_format = 'YYYY-MM-DD HH:mm:ss'
+            elif (values != values.dt.normalize()).any():
+                datetime_format = 'YYYY-MM-DD HH:mm'
+            else:
+                datetime_format = 'YYYY-MM-DD'
+            column_config[col] = st.column_config.DatetimeColumn(format=datetime_format)
+        elif not styled and pd.api.types.is_numeric_dtype(values) and not pd.api.types.is_bool_dtype(values):
+            # Fall back to printf-style formats (without separators) when the Styler cannot be used
+            decimals = COLUMN_DECIMALS.get(col, DEFAULT_DECIMALS)
+            column_config[col] = st.column_config.NumberColumn(format=f"%.{decimals}f")
+    return column_config
+
+# Format numeric columns with the display locale while keeping the underlying values numeric
+def style_numbers(df):
+    numeric_columns = df.select_dtypes(include='number').columns
+    formatters = {col: (lambda x, d=COLUMN_DECIMALS.get(col, DEFAULT_DECIMALS): format_number(x, d)) for col in numeric_columns}
+    return df.style.format(formatters)
+
+# Display a DataFrame with locale-aware number formatting applied at render time
+def display_dataframe(df):
+    styled = df.size <= MAX_STYLED_CELLS
+    data = style_numbers(df) if styled else df
+    st.dataframe(data, column_config=build_column_config(df, styled=styled))
 
 # Apply default sort and display the data
 def display_data_with_default_sort(df, sort_column):
     if not df.empty:
         df_sorted = df.sort_values(by=sort_column, ascending=False)
-        st.dataframe(df_sorted)
+        display_dataframe(df_sorted)
     else:
         st.error("No data found.")
 
@@ -14,5 +93,5 @@ def escape_markdown(text):
     markdown_special_chars = ["\\", "`", "*", "_", "{", "}", "[", "]", "(", ")", "#", "+", "-", ".", "!", "|", ":", "$", ">"]
     for char in markdown_special_chars:
         text = text.replace(char, f"\\{char}")
-    
-    return text
\ No newline at end of file
+
+    return text
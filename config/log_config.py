@@ -22,6 +22,10 @@ def setup_logging():
     logger = logging.getLogger(__name__)
     logger.setLevel(logging.INFO)
 
+    # Reuse the existing handler when several modules set up logging
+    if logger.handlers:
+        return logger
+
     # Set up a TimedRotatingFileHandler
     handler = TimedRotatingFileHandler(
     log_filename,  # Log file name
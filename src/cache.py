@@ -0,0 +1,236 @@
+import hashlib
+import json
+import os
+import sqlite3
+import time
+from datetime import date, datetime, timedelta
+from zoneinfo import ZoneInfo
+import config.cache_config as cache_config
+import config.log_config
+
+# Initialize the logger
+logger = config.log_config.setup_logging()
+
+# Polygon interprets aggregate date ranges in US/Eastern, except for forex and crypto whose days start at midnight UTC
+MARKET_TIMEZONE = ZoneInfo('America/New_York')
+UTC = ZoneInfo('UTC')
+
+SCHEMA = """
+CREATE TABLE IF NOT EXISTS responses (
+    key TEXT PRIMARY KEY,
+    endpoint TEXT NOT NULL,
+    params TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    fetched_at REAL NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bars (
+    series TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (series, timestamp)
+);
+CREATE TABLE IF NOT EXISTS bar_ranges (
+    series TEXT NOT NULL,
+    from_date TEXT NOT NULL,
+    to_date TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bar_series (
+    series TEXT PRIMARY KEY,
+    stored_at REAL NOT NULL,
+    checked_on TEXT
+);
+CREATE TABLE IF NOT EXISTS ticks (
+    series TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    sequence INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (series, timestamp, sequence)
+);
+CREATE TABLE IF NOT EXISTS tick_ranges (
+    series TEXT NOT NULL,
+    from_timestamp INTEGER NOT NULL,
+    to_timestamp INTEGER NOT NULL
+);
+"""
+
+
+# Raised in offline mode when a request cannot be served from the cache
+class CacheMissError(Exception):
+    pass
+
+
+# Build a stable cache key from an endpoint and its parameters
+def make_cache_key(endpoint, params):
+    encoded = json.dumps(params, sort_keys=True, default=str)
+    return hashlib.sha256(f"{endpoint}:{encoded}".encode('utf-8')).hexdigest()
+
+
+# Unix milliseconds at midnight of the given day in the market's timezone (US/Eastern by default)
+def market_day_start_ms(day, timezone=MARKET_TIMEZONE):
+    return int(datetime(day.year, day.month, day.day, tzinfo=timezone).timestamp() * 1000)
+
+
+# Persistent SQLite store for Polygon responses and aggregate bars
+class ResponseCache:
+    def __init__(self, path=cache_config.CACHE_PATH, ttl=None):
+        self.path = path
+        self.ttl = cache_config.CACHE_TTL if ttl is None else ttl
+        directory = os.path.dirname(path)
+        if directory and not os.path.exists(directory):
+            os.makedirs(directory)
+        with self._connect() as conn:
+            conn.executescript(SCHEMA)
+            # Caches created before split checks were recorded lack the checked_on column
+            columns = [row[1] for row in conn.execute("PRAGMA table_info(bar_series)")]
+            if 'checked_on' not in columns:
+                conn.execute("ALTER TABLE bar_series ADD COLUMN checked_on TEXT")
+
+    # Open a new connection per operation so the cache can be shared across Streamlit threads
+    def _connect(self):
+        return sqlite3.connect(self.path, timeout=30)
+
+    # Get a cached payload, or None when missing or expired (expiry is ignored when allow_stale is set)
+    def get(self, endpoint, params, allow_stale=False):
+        ttl = self.ttl.get(endpoint)
+        if ttl == 0 and not allow_stale:
+            return None
+        with self._connect() as conn:
+            row = conn.execute("SELECT payload, fetched_at FROM responses WHERE key = ?", (make_cache_key(endpoint, params),)).fetchone()
+        if row is None:
+            return None
+        payload, fetched_at = row
+        if not allow_stale and ttl is not None and time.time() - fetched_at > ttl:
+            logger.info(f"Cached {endpoint} response expired")
+            return None
+        return json.loads(payload)
+
+    # Store a payload for an endpoint and its parameters
+    def put(self, endpoint, params, payload):
+        if self.ttl.get(endpoint) == 0:
+            return
+        with self._connect() as conn:
+            conn.execute(
+                "INSERT OR REPLACE INTO responses (key, endpoint, params, payload, fetched_at) VALUES (?, ?, ?, ?, ?)",
+                (make_cache_key(endpoint, params), endpoint, json.dumps(params, sort_keys=True, default=str), json.dumps(payload), time.time()),
+            )
+
+    # Get the stored raw bars of a series between two dates (inclusive, days starting at midnight in timezone)
+    def get_bars(self, series, from_date, to_date, timezone=MARKET_TIMEZONE):
+        start = market_day_start_ms(from_date, timezone)
+        end = market_day_start_ms(to_date + timedelta(days=1), timezone)
+        with self._connect() as conn:
+            rows = conn.execute(
+                "SELECT payload FROM bars WHERE series = ? AND timestamp >= ? AND timestamp < ? ORDER BY timestamp",
+                (series, start, end),
+            ).fetchall()
+        return [json.loads(row[0]) for row in rows]
+
+    # Store raw bars and record the date range they cover (days starting at midnight in timezone)
+    def put_bars(self, series, from_date, to_date, bars, timezone=MARKET_TIMEZONE):
+        with self._connect() as conn:
+            conn.executemany(
+                "INSERT OR REPLACE INTO bars (series, timestamp, payload) VALUES (?, ?, ?)",
+                [(series, bar['t'], json.dumps(bar)) for bar in bars],
+            )
+            conn.execute("INSERT OR IGNORE INTO bar_series (series, stored_at) VALUES (?, ?)", (series, time.time()))
+            # Only complete days are marked as covered so the current day is always topped up
+            last_complete_day = datetime.now(timezone).date() - timedelta(days=1)
+            covered_to = min(to_date, last_complete_day)
+            if from_date <= covered_to:
+                self._add_range(conn, series, from_date, covered_to)
+
+    # When the first bars of a series still stored were fetched (0 for bars stored before this was recorded, None when there are none)
+    def bars_stored_at(self, series):
+        with self._connect() as conn:
+            row = conn.execute("SELECT stored_at FROM bar_series WHERE series = ?", (series,)).fetchone()
+            if row is not None:
+                return row[0]
+            return 0.0 if self._load_ranges(conn, series) else None
+
+    # Day the stored bars of a series were last checked for splits, or None when they never were
+    def bars_checked_on(self, series):
+        with self._connect() as conn:
+            row = conn.execute("SELECT checked_on FROM bar_series WHERE series = ?", (series,)).fetchone()
+        return date.fromisoformat(row[0]) if row and row[0] else None
+
+    # Record the day the stored bars of a series were checked for splits
+    def set_bars_checked_on(self, series, day):
+        with self._connect() as conn:
+            conn.execute(
+                "INSERT INTO bar_series (series, stored_at, checked_on) VALUES (?, 0, ?) ON CONFLICT (series) DO UPDATE SET checked_on = excluded.checked_on",
+                (series, day.isoformat()),
+            )
+
+    # Forget the stored bars and covered ranges of a series
+    def clear_bars(self, series):
+        with self._connect() as conn:
+            for table in ('bars', 'bar_ranges', 'bar_series'):
+                conn.execute(f"DELETE FROM {table} WHERE series = ?", (series,))
+
+    # Merge a covered date range into the stored ranges of a series
+    def _add_range(self, conn, series, from_date, to_date):
+        ranges = self._load_ranges(conn, series) + [(from_date, to_date)]
+        ranges.sort()
+        merged = [ranges[0]]
+        for start, end in ranges[1:]:
+            last_start, last_end = merged[-1]
+            if start <= last_end + timedelta(days=1):
+                merged[-1] = (last_start, max(last_end, end))
+            else:
+                merged.append((start, end))
+        conn.execute("DELETE FROM bar_ranges WHERE series = ?", (series,))
+        conn.executemany(
+            "INSERT INTO bar_ranges (series, from_date, to_date) VALUES (?, ?, ?)",
+            [(series, start.isoformat(), end.isoformat()) for start, end in merged],
+        )
+
+    def _load_ranges(self, conn, series):
+        rows = conn.execute("SELECT from_date, to_date FROM bar_ranges WHERE series = ?", (series,)).fetchall()
+        return [(date.fromisoformat(start), date.fromisoformat(end)) for start, end in rows]
+
+    # Get the sub-ranges of [from_date, to_date] that are not stored yet
+    def missing_bar_ranges(self, series, from_date, to_date):
+        with self._connect() as conn:
+            ranges = sorted(self._load_ranges(conn, series))
+        missing = []
+        cursor = from_date
+        for start, end in ranges:
+            if end < cursor or start > to_date:
+                continue
+            if start > cursor:
+                missing.append((cursor, start - timedelta(days=1)))
+            cursor = max(cursor, end + timedelta(days=1))
+        if cursor <= to_date:
+            missing.append((cursor, to_date))
+        return missing
+
+    # Store one page of trades or quotes (keyed by SIP timestamp and sequence number)
+    def put_ticks(self, series, ticks):
+        with self._connect() as conn:
+            conn.executemany(
+                "INSERT OR REPLACE INTO ticks (series, timestamp, sequence, payload) VALUES (?, ?, ?, ?)",
+                [(series, tick['sip_timestamp'], tick.get('sequence_number', 0), json.dumps(tick)) for tick in ticks],
+            )
+
+    # Record that every tick of [from_timestamp, to_timestamp) is stored
+    def add_tick_range(self, series, from_timestamp, to_timestamp):
+        with self._connect() as conn:
+            conn.execute("INSERT INTO tick_ranges (series, from_timestamp, to_timestamp) VALUES (?, ?, ?)", (series, from_timestamp, to_timestamp))
+
+    # Whether [from_timestamp, to_timestamp) lies within a range stored in full
+    def has_tick_range(self, series, from_timestamp, to_timestamp):
+        with self._connect() as conn:
+            row = conn.execute(
+                "SELECT 1 FROM tick_ranges WHERE series = ? AND from_timestamp <= ? AND to_timestamp >= ?",
+                (series, from_timestamp, to_timestamp),
+            ).fetchone()
+        return row is not None
+
+    # Get the stored ticks of [from_timestamp, to_timestamp) in time order, up to limit ticks
+    def get_ticks(self, series, from_timestamp, to_timestamp, limit=None):
+        with self._connect() as conn:
+            rows = conn.execute(
+                "SELECT payload FROM ticks WHERE series = ? AND timestamp >= ? AND timestamp < ? ORDER BY timestamp, sequence LIMIT ?",
+                (series, from_timestamp, to_timestamp, -1 if limit is None else limit),
+            ).fetchall()
+        return [json.loads(row[0]) for row in rows]
@@ -1,11 +1,154 @@
 import streamlit as st
 import plotly.graph_objects as go
+from plotly.subplots import make_subplots
+from indicators import INDICATORS, compute_indicator
+from market_calendar import get_rangebreaks
 
-# Plot a Candlestick Chart
-def plot_candlestick_chart(df):
-    fig = go.Figure(data=[go.Candlestick(x=df['Date'],
+# Colors of rising and falling bars
+UP_COLOR = '#26a69a'
+DOWN_COLOR = '#ef5350'
+
+# Relative heights of the chart panes
+PRICE_PANE_HEIGHT = 3.0
+VOLUME_PANE_HEIGHT = 1.0
+INDICATOR_PANE_HEIGHT = 1.2
+
+# Build the multi-pane candlestick figure: price with overlays, volume, then one pane per oscillator
+def build_candlestick_figure(df, indicators=None, show_volume=True, extra_holidays=None, market='stocks'):
+    df = df.sort_index()  # Indicators need the bars in chronological order
+    computed = [(name, compute_indicator(df, name, **params)) for name, params in (indicators or [])]
+    show_volume = show_volume and 'Volume' in df.columns
+
+    # Pane titles and heights, top to bottom
+    panes = ['Price'] + (['Volume'] if show_volume else [])
+    for name, _ in computed:
+        pane = INDICATORS[name]['pane']
+        if pane != 'price' and pane not in panes:
+            panes.append(pane)
+    heights = [PRICE_PANE_HEIGHT] + [VOLUME_PANE_HEIGHT if pane == 'Volume' else INDICATOR_PANE_HEIGHT for pane in panes[1:]]
+    fig = make_subplots(rows=len(panes), cols=1, shared_xaxes=True, vertical_spacing=0.03,
+                        row_heights=[height / sum(heights) for height in heights], subplot_titles=[''] + panes[1:])
+
+    fig.add_trace(go.Candlestick(x=df.index,
                 open=df['Open'], high=df['High'],
-                low=df['Low'], close=df['Close'])])
+                low=df['Low'], close=df['Close'], name='Price',
+                increasing_line_color=UP_COLOR, decreasing_line_color=DOWN_COLOR), row=1, col=1)
+
+    if show_volume:
+        colors = [UP_COLOR if close >= open_ else DOWN_COLOR for open_, close in zip(df['Open'], df['Close'])]
+        fig.add_trace(go.Bar(x=df.index, y=df['Volume'], name='Volume', marker_color=colors, showlegend=False), row=2, col=1)
+
+    for name, values in computed:
+        spec = INDICATORS[name]
+        row = 1 if spec['pane'] == 'price' else panes.index(spec['pane']) + 1
+        for column in values.columns:
+            if column.endswith('Histogram'):
+                fig.add_trace(go.Bar(x=values.index, y=values[column], name=column, opacity=0.5), row=row, col=1)
+            else:
+                fig.add_trace(go.Scatter(x=values.index, y=values[column], name=column, mode='lines', line={'width': 1}), row=row, col=1)
+        for level in spec.get('levels', []):
+            fig.add_hline(y=level, line_dash='dot', line_color='gray', row=row, col=1)
+
+    # Hide weekends, market holidays and overnight hours so bars are contiguous
+    fig.update_xaxes(rangebreaks=get_rangebreaks(df.index, extra_holidays, market))
+    fig.update_layout(title='Candlestick Chart', xaxis_rangeslider_visible=False, height=400 + 150 * (len(panes) - 1))
+    return fig
+
+# Plot a Candlestick Chart with volume and optional indicators, given as a list of (name, params) from indicators.INDICATORS
+# extra_holidays ({date: name}) adds closures missing from the built-in calendar to the hidden days; market ('stocks', 'fx' or 'crypto') sets which gaps are hidden
+def plot_candlestick_chart(df, indicators=None, show_volume=True, extra_holidays=None, market='stocks'):
+    fig = build_candlestick_figure(df, indicators, show_volume, extra_holidays, market)
+    st.plotly_chart(fig, use_container_width=True)
+
+# Plot one line per column, e.g. rebased returns or relative strength
+def plot_line_chart(df, title, percent=False, reference=None, extra_holidays=None, market='stocks'):
+    fig = go.Figure()
+    for column in df.columns:
+        fig.add_trace(go.Scatter(x=df.index, y=df[column], name=column, mode='lines'))
+    if reference is not None:
+        fig.add_hline(y=reference, line_dash='dot', line_color='gray')
+    fig.update_xaxes(rangebreaks=get_rangebreaks(df.index, extra_holidays, market))
+    fig.update_layout(title=title, yaxis_tickformat='.0%' if percent else None, hovermode='x unified')
+    st.plotly_chart(fig, use_container_width=True)
 
-    fig.update_layout(title='Candlestick Chart', xaxis_rangeslider_visible=False)
+# Plot a correlation matrix as an annotated heatmap
+def plot_correlation_heatmap(corr, title='Return Correlation'):
+    fig = go.Figure(data=go.Heatmap(z=corr.values, x=corr.columns, y=corr.index, zmin=-1, zmax=1,
+                                    colorscale='RdBu', text=corr.round(2).values, texttemplate='%{text}'))
+    fig.update_layout(title=title, yaxis_autorange='reversed')
     st.plotly_chart(fig, use_container_width=True)
+
+# Build the NBBO figure: bid and ask prices on top, the bid-ask spread below
+def build_spread_figure(quotes):
+    fig = make_subplots(rows=2, cols=1, shared_xaxes=True, vertical_spacing=0.05, row_heights=[0.65, 0.35], subplot_titles=['', 'Spread'])
+    fig.add_trace(go.Scatter(x=quotes.index, y=quotes['Bid'], name='Bid', mode='lines', line={'shape': 'hv', 'color': UP_COLOR}), row=1, col=1)
+    fig.add_trace(go.Scatter(x=quotes.index, y=quotes['Ask'], name='Ask', mode='lines', line={'shape': 'hv', 'color': DOWN_COLOR}), row=1, col=1)
+    fig.add_trace(go.Scatter(x=quotes.index, y=quotes['Spread'], name='Spread', mode='lines', line={'shape': 'hv'}, showlegend=False), row=2, col=1)
+    fig.update_layout(title='NBBO Spread', hovermode='x unified', height=500)
+    return fig
+
+# Plot the NBBO bid, ask and spread over time
+def plot_spread_chart(quotes):
+    st.plotly_chart(build_spread_figure(quotes), use_container_width=True)
+
+# Plot the distribution of trade sizes (log scale, as a few block trades dwarf the odd lots)
+def plot_trade_size_histogram(trades, bins=50):
+    fig = go.Figure(data=go.Histogram(x=trades['Size'], nbinsx=bins, name='Trades'))
+    fig.update_layout(title='Trade Size Distribution', xaxis_title='Shares', yaxis_title='Trades', yaxis_type='log', bargap=0.05)
+    st.plotly_chart(fig, use_container_width=True)
+
+# Charts of a company's results over time, drawn from financial_metrics.metrics_history: title -> (columns, kind)
+FINANCIAL_TREND_CHARTS = {
+    'Revenue and Net Income': (['Revenue', 'Net Income'], 'bar'),
+    'Diluted EPS': (['Diluted EPS'], 'bar'),
+    'Margins': (['Gross Margin (%)', 'Operating Margin (%)', 'Net Margin (%)'], 'line'),
+    'Cash Flow': (['Operating Cash Flow', 'Free Cash Flow'], 'bar'),
+}
+
+# Charts of a company's valuation over time, drawn from valuation.valuation_multiples
+VALUATION_CHARTS = {
+    'Valuation Multiples': (['P/E', 'P/S', 'P/B', 'EV/EBITDA'], 'line'),
+    'Yields (%)': (['FCF Yield (%)', 'Dividend Yield (%)'], 'line'),
+}
+
+# Build one figure per chart with the periods ('Q1 2024') on the x axis; series without any value are left out
+def build_period_figures(history, charts):
+    figures = []
+    for title, (columns, kind) in charts.items():
+        columns = [column for column in columns if column in history.columns and history[column].notna().any()]
+        if not columns:
+            continue
+        fig = go.Figure()
+        for column in columns:
+            if kind == 'bar':
+                fig.add_trace(go.Bar(x=history['Period'], y=history[column], name=column))
+            else:
+                fig.add_trace(go.Scatter(x=history['Period'], y=history[column], name=column, mode='lines+markers'))
+        fig.update_xaxes(type='category')
+        fig.update_layout(title=title, barmode='group', hovermode='x unified')
+        figures.append(fig)
+    return figures
+
+# Build the trend figures of financial_metrics.metrics_history
+def build_financial_trend_figures(history):
+    return build_period_figures(history, FINANCIAL_TREND_CHARTS)
+
+# Plot revenue, net income, EPS, margins and cash flow over time
+def plot_financial_trends(history):
+    for fig in build_financial_trend_figures(history):
+        st.plotly_chart(fig, use_container_width=True)
+
+# Plot price multiples and yields at the end of each period
+def plot_valuation_history(multiples):
+    for fig in build_period_figures(multiples, VALUATION_CHARTS):
+        st.plotly_chart(fig, use_container_width=True)
+
+# Plot one bar chart per metric comparing peers, best first
+def plot_peer_comparison(table, metrics, lower_is_better=()):
+    for metric in metrics:
+        values = table[metric].dropna().sort_values(ascending=metric in lower_is_better)
+        if values.empty:
+            continue
+        fig = go.Figure(data=go.Bar(x=values.index, y=values, name=metric))
+        fig.update_layout(title=metric)
+        st.plotly_chart(fig, use_container_width=True)
@@ -0,0 +1,30 @@
+import pandas as pd
+
+# Multi-ticker comparison helpers working on close prices (one column per ticker, DatetimeIndex)
+
+# Align the close prices of several OHLCV DataFrames on the dates every ticker traded
+def align_close_prices(frames):
+    if not frames:
+        return pd.DataFrame()
+    return pd.concat({ticker: df['Close'] for ticker, df in frames.items()}, axis=1).dropna()
+
+# Cumulative returns rebased to zero at the first common date
+def rebased_returns(prices):
+    return prices / prices.iloc[0] - 1
+
+# Performance of each ticker relative to the benchmark (above 1 means it outperformed since the first date)
+def relative_strength(prices, benchmark):
+    growth = prices / prices.iloc[0]
+    return growth.drop(columns=benchmark).div(growth[benchmark], axis=0)
+
+# Correlation matrix of period-over-period returns
+def return_correlation(prices):
+    return prices.pct_change().dropna().corr()
+
+# Total return and volatility of period returns per ticker
+def summarize_returns(prices):
+    returns = prices.pct_change().dropna()
+    return pd.DataFrame({
+        'Total Return (%)': (prices.iloc[-1] / prices.iloc[0] - 1) * 100,
+        'Volatility (%)': returns.std() * 100,
+    }).rename_axis('Ticker')
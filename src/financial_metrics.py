@@ -0,0 +1,170 @@
+import pandas as pd
+
+# Financial ratios and growth rates derived from the line items of /vX/reference/financials reports
+# Line items keep Polygon's keys ('revenues', 'net_income_loss'...); a metric whose inputs are missing or whose
+# denominator is zero is NaN rather than computed from a zero
+# Balances are taken at the end of the period and returns are per period (a quarterly ROE covers one quarter)
+
+# Columns describing the period of each report
+PERIOD_COLUMNS = ['Period', 'Timeframe', 'Fiscal Year', 'Fiscal Period', 'Start Date', 'End Date']
+
+# Debt line items of Polygon's balance sheet: only long-term debt is reported, so short-term borrowings are left out
+DEBT_ITEMS = ('long_term_debt',)
+
+# Line items only some filers report, tried in order
+INTEREST_ITEMS = ('interest_expense_operating', 'interest_expense')
+
+# Metrics in display order; margins and returns are percentages
+METRIC_COLUMNS = [
+    'Gross Margin (%)', 'Operating Margin (%)', 'Net Margin (%)', 'ROE (%)', 'ROA (%)', 'ROIC (%)',
+    'Current Ratio', 'Quick Ratio', 'Debt to Equity', 'Interest Coverage', 'Free Cash Flow',
+]
+
+# Values whose growth is tracked, by column name prefix
+GROWTH_ITEMS = {'Revenue': 'revenues', 'Net Income': 'net_income_loss', 'Diluted EPS': 'diluted_earnings_per_share', 'Free Cash Flow': 'Free Cash Flow'}
+
+# Line items charted and compared next to the metrics
+HEADLINE_ITEMS = {'Revenue': 'revenues', 'Net Income': 'net_income_loss', 'Diluted EPS': 'diluted_earnings_per_share', 'Operating Cash Flow': 'net_cash_flow_from_operating_activities'}
+
+# Metrics peers can be ranked on; a higher value ranks better except for LOWER_IS_BETTER
+PEER_METRICS = list(HEADLINE_ITEMS) + METRIC_COLUMNS + [f"{name} Growth YoY (%)" for name in GROWTH_ITEMS]
+LOWER_IS_BETTER = {'Debt to Equity'}
+DEFAULT_PEER_METRICS = ['Revenue Growth YoY (%)', 'Operating Margin (%)', 'Net Margin (%)', 'ROE (%)']
+
+# How free cash flow is derived, shown next to the metrics
+FREE_CASH_FLOW_NOTE = ("Free cash flow is operating cash flow less the net cash used in investing, as Polygon's cash flow statements "
+                       "have no capital expenditure line; it is blank when a filing lacks either.")
+
+# Reports a year apart may end a few days off (fiscal years ending on the last Saturday of September)
+YEAR_TOLERANCE_DAYS = 14
+
+
+# One row per report in the given order: the period columns followed by every line item key
+def line_items(reports):
+    records = []
+    for report in reports:
+        record = {
+            'Period': report.period_label,
+            'Timeframe': report.timeframe,
+            'Fiscal Year': report.fiscal_year,
+            'Fiscal Period': report.fiscal_period,
+            'Start Date': report.start_date,
+            'End Date': report.end_date,
+        }
+        for section_data in report.financials.values():
+            for key, value in section_data.items():
+                record.setdefault(key, value.value)
+        records.append(record)
+    df = pd.DataFrame(records, columns=PERIOD_COLUMNS if not records else None)
+    for col in ['Start Date', 'End Date']:
+        df[col] = pd.to_datetime(df[col])
+    for key in df.columns.difference(PERIOD_COLUMNS):
+        df[key] = pd.to_numeric(df[key], errors='coerce')
+    return df
+
+# Line item by key, all NaN when no report has it
+def item(items, key):
+    if key in items.columns:
+        return items[key].astype('float64')
+    return pd.Series(float('nan'), index=items.index)
+
+# First of several alternative line items each report has
+def first_item(items, keys):
+    values = item(items, keys[0])
+    for key in keys[1:]:
+        values = values.fillna(item(items, key))
+    return values
+
+# Division that yields NaN instead of infinity for a zero denominator
+def safe_divide(numerator, denominator):
+    return numerator / denominator.where(denominator != 0)
+
+# Total debt from the debt line items a report has (NaN when it has none)
+def total_debt(items):
+    present = [key for key in DEBT_ITEMS if key in items.columns]
+    if not present:
+        return item(items, DEBT_ITEMS[0])
+    return items[present].sum(axis=1, min_count=1)
+
+# Operating cash flow less the net cash invested: Polygon's cash flow statement has no capital expenditure line, so the
+# investing outflow (capex along with acquisitions and securities bought) stands in for it; a net inflow counts as nothing invested
+def free_cash_flow(items):
+    return item(items, 'net_cash_flow_from_operating_activities') + item(items, 'net_cash_flow_from_investing_activities').clip(upper=0)
+
+# Income tax over pre-tax income, bounded to [0, 1] as losses and tax credits make the raw ratio meaningless
+def effective_tax_rate(items):
+    rate = safe_divide(item(items, 'income_tax_expense_benefit'), item(items, 'income_loss_from_continuing_operations_before_tax'))
+    return rate.clip(0, 1)
+
+# Profitability, liquidity and leverage metrics of each report
+def compute_metrics(items):
+    revenues = item(items, 'revenues')
+    gross_profit = item(items, 'gross_profit').fillna(revenues - item(items, 'cost_of_revenue'))
+    operating_income = item(items, 'operating_income_loss')
+    net_income = item(items, 'net_income_loss')
+    equity = item(items, 'equity')
+    current_liabilities = item(items, 'current_liabilities')
+    debt = total_debt(items)
+    nopat = operating_income * (1 - effective_tax_rate(items))
+    return pd.DataFrame({
+        'Gross Margin (%)': safe_divide(gross_profit, revenues) * 100,
+        'Operating Margin (%)': safe_divide(operating_income, revenues) * 100,
+        'Net Margin (%)': safe_divide(net_income, revenues) * 100,
+        'ROE (%)': safe_divide(net_income, equity) * 100,
+        'ROA (%)': safe_divide(net_income, item(items, 'assets')) * 100,
+        'ROIC (%)': safe_divide(nopat, equity + debt) * 100,
+        'Current Ratio': safe_divide(item(items, 'current_assets'), current_liabilities),
+        'Quick Ratio': safe_divide(item(items, 'current_assets') - item(items, 'inventory'), current_liabilities),
+        'Debt to Equity': safe_divide(debt, equity),
+        'Interest Coverage': safe_divide(operating_income, first_item(items, INTEREST_ITEMS).abs()),
+        'Free Cash Flow': free_cash_flow(items),
+    }, index=items.index, columns=METRIC_COLUMNS)
+
+# Relative change from a base value; a negative base gives the direction of the change (a smaller loss is growth)
+def growth(current, previous):
+    return safe_divide(current - previous, previous.abs()) * 100
+
+# Period-over-period growth (against the previous report of the same timeframe) and year-over-year growth
+# (against the report of the same timeframe ending a year earlier) of the GROWTH_ITEMS
+def growth_rates(items, metrics):
+    values = pd.DataFrame({name: metrics[key] if key in metrics.columns else item(items, key) for name, key in GROWTH_ITEMS.items()}, index=items.index)
+    columns = [f"{name} Growth {kind} (%)" for name in GROWTH_ITEMS for kind in ('PoP', 'YoY')]
+    rates = pd.DataFrame(index=items.index, columns=columns, dtype='float64')
+    tolerance = pd.Timedelta(days=YEAR_TOLERANCE_DAYS)
+    for _, group in items.groupby(items['Timeframe'].fillna(''), sort=False):
+        ends = group['End Date'].sort_values()
+        current = values.loc[ends.index]
+        previous = current.shift(1)
+        year_ago = pd.DataFrame(index=ends.index, columns=values.columns, dtype='float64')
+        for index, end_date in ends.items():
+            gaps = (ends - (end_date - pd.DateOffset(years=1))).abs()
+            if gaps.min() <= tolerance:
+                year_ago.loc[index] = values.loc[gaps.idxmin()]
+        for name in GROWTH_ITEMS:
+            rates.loc[ends.index, f"{name} Growth PoP (%)"] = growth(current[name], previous[name])
+            rates.loc[ends.index, f"{name} Growth YoY (%)"] = growth(current[name], year_ago[name])
+    return rates
+
+# Metrics and growth rates of the line items of each report
+def metrics_of(items):
+    metrics = compute_metrics(items)
+    return pd.concat([metrics, growth_rates(items, metrics)], axis=1)
+
+# Metrics and growth rates of a list of FinancialReport, one row per report in the given order
+def financial_metrics(reports):
+    return metrics_of(line_items(reports))
+
+# Period, headline items, metrics and growth rates of each report indexed by period end, oldest first
+def metrics_history(reports):
+    items = line_items(reports)
+    headline = pd.DataFrame({name: item(items, key) for name, key in HEADLINE_ITEMS.items()}, index=items.index)
+    history = pd.concat([items[['Period', 'Timeframe', 'End Date']], headline, metrics_of(items)], axis=1)
+    return history.sort_values('End Date').set_index('End Date')
+
+# Rank peers (one row per ticker) on each metric, 1 being the best, and order them by their average rank
+# Tickers missing a metric are left out of its ranking rather than ranked last
+def rank_peers(table, metrics):
+    ranks = pd.DataFrame({f"{metric} Rank": table[metric].rank(ascending=metric in LOWER_IS_BETTER, method='min') for metric in metrics}, index=table.index)
+    ranking = pd.concat([table[metrics], ranks], axis=1)
+    ranking['Average Rank'] = ranks.mean(axis=1)
+    return ranking.sort_values('Average Rank')
@@ -0,0 +1,120 @@
+import random
+import threading
+import time
+from datetime import datetime, timezone
+from email.utils import parsedate_to_datetime
+import requests
+import config.api_config as api_config
+import config.log_config
+
+# Initialize the logger
+logger = config.log_config.setup_logging()
+
+# Status codes that are worth retrying
+RETRY_STATUS_CODES = {429, 500, 502, 503, 504}
+
+
+# Token bucket limiter: holds up to `capacity` tokens and refills them evenly over `period` seconds
+class TokenBucket:
+    def __init__(self, capacity, period, clock=time.monotonic, sleep=time.sleep):
+        self.capacity = capacity
+        self.refill_rate = capacity / period
+        self.tokens = float(capacity)
+        self.clock = clock
+        self.sleep = sleep
+        self.updated_at = clock()
+        self.lock = threading.Lock()
+
+    # Block until a token is available, then consume it
+    def acquire(self):
+        while True:
+            with self.lock:
+                now = self.clock()
+                self.tokens = min(self.capacity, self.tokens + (now - self.updated_at) * self.refill_rate)
+                self.updated_at = now
+                if self.tokens >= 1:
+                    self.tokens -= 1
+                    return
+                wait = (1 - self.tokens) / self.refill_rate
+            logger.info(f"Rate limit reached, waiting {wait:.2f}s for a request token")
+            self.sleep(wait)
+
+
+# Parse a Retry-After header given either in seconds or as an HTTP date
+def parse_retry_after(value):
+    if not value:
+        return None
+    try:
+        return max(0.0, float(value))
+    except ValueError:
+        pass
+    try:
+        retry_at = parsedate_to_datetime(value)
+    except (TypeError, ValueError):
+        return None
+    if retry_at.tzinfo is None:
+        retry_at = retry_at.replace(tzinfo=timezone.utc)
+    return max(0.0, (retry_at - datetime.now(timezone.utc)).total_seconds())
+
+
+# HTTP session with timeouts, retries with exponential backoff and jitter, and an optional rate limiter
+class HttpSession:
+    def __init__(self, session=None, rate_limiter=None, connect_timeout=api_config.CONNECT_TIMEOUT,
+                 read_timeout=api_config.READ_TIMEOUT, max_retries=api_config.MAX_RETRIES,
+                 backoff_base=api_config.BACKOFF_BASE, backoff_max=api_config.BACKOFF_MAX, sleep=time.sleep):
+        self.session = session or requests.Session()
+        self.rate_limiter = rate_limiter
+        self.timeout = (connect_timeout, read_timeout)
+        self.max_retries = max_retries
+        self.backoff_base = backoff_base
+        self.backoff_max = backoff_max
+        self.sleep = sleep
+
+    # Full jitter: a random delay between 0 and the exponential backoff cap
+    def backoff_delay(self, attempt):
+        return random.uniform(0, min(self.backoff_max, self.backoff_base * (2 ** attempt)))
+
+    # Send a GET request, retrying on connection errors, timeouts and retryable status codes
+    def get(self, url, params=None):
+        attempt = 0
+        while True:
+            if self.rate_limiter:
+                self.rate_limiter.acquire()
+            try:
+                response = self.session.get(url, params=params, timeout=self.timeout)
+            except (requests.ConnectionError, requests.Timeout) as e:
+                if attempt >= self.max_retries:
+                    logger.error(f"Request failed after {attempt + 1} attempt(s): {e}")
+                    raise
+                delay = self.backoff_delay(attempt)
+                logger.warning(f"Request error ({e}), retrying in {delay:.2f}s (attempt {attempt + 1}/{self.max_retries})")
+            else:
+                if response.status_code not in RETRY_STATUS_CODES or attempt >= self.max_retries:
+                    return response
+                retry_after = parse_retry_after(response.headers.get('Retry-After'))
+                delay = min(self.backoff_max, retry_after) if retry_after is not None else self.backoff_delay(attempt)
+                logger.warning(f"HTTP {response.status_code}, retrying in {delay:.2f}s (attempt {attempt + 1}/{self.max_retries})")
+            self.sleep(delay)
+            attempt += 1
+
+
+# Create an HttpSession using the settings in config/api_config.py
+def create_http_session(session=None):
+    rate_limiter = None
+    if api_config.RATE_LIMIT_REQUESTS > 0:
+        rate_limiter = TokenBucket(api_config.RATE_LIMIT_REQUESTS, api_config.RATE_LIMIT_PERIOD)
+    return HttpSession(session=session, rate_limiter=rate_limiter)
+
+
+# Shared session so every request counts against the same rate limit
+_default_session = None
+_default_session_lock = threading.Lock()
+
+
+# Get the process-wide HttpSession, creating it on first use
+def get_http_session():
+    global _default_session
+    with _default_session_lock:
+        if _default_session is None:
+            _default_session = create_http_session()
+        return _default_session
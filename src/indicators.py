@@ -0,0 +1,108 @@
+import pandas as pd
+
+# Technical indicators computed locally from an OHLCV DataFrame (DatetimeIndex with Open, High, Low, Close, Volume)
+
+# Simple moving average
+def sma(df, window=20, column='Close'):
+    return df[column].rolling(window=window, min_periods=window).mean()
+
+# Exponential moving average
+def ema(df, window=20, column='Close'):
+    return df[column].ewm(span=window, adjust=False, min_periods=window).mean()
+
+# Bollinger Bands: moving average plus/minus a number of (population) standard deviations
+def bollinger_bands(df, window=20, num_std=2.0, column='Close'):
+    middle = df[column].rolling(window=window, min_periods=window).mean()
+    std = df[column].rolling(window=window, min_periods=window).std(ddof=0)
+    return pd.DataFrame({'Upper': middle + num_std * std, 'Middle': middle, 'Lower': middle - num_std * std}, index=df.index)
+
+# Volume weighted average price of the typical price; resets every day for intraday bars, anchored at the first bar otherwise
+def vwap(df):
+    typical_price = (df['High'] + df['Low'] + df['Close']) / 3
+    price_volume = typical_price * df['Volume']
+    days = df.index.normalize()
+    if days.duplicated().any():
+        return price_volume.groupby(days).cumsum() / df['Volume'].groupby(days).cumsum()
+    return price_volume.cumsum() / df['Volume'].cumsum()
+
+# Relative strength index with Wilder's smoothing
+def rsi(df, window=14, column='Close'):
+    delta = df[column].diff()
+    gain = delta.clip(lower=0).ewm(alpha=1 / window, adjust=False, min_periods=window).mean()
+    loss = (-delta.clip(upper=0)).ewm(alpha=1 / window, adjust=False, min_periods=window).mean()
+    # A window without losses has an RSI of 100
+    return (100 - 100 / (1 + gain / loss)).where(loss != 0, 100.0).where(gain.notna())
+
+# Moving average convergence divergence
+def macd(df, fast=12, slow=26, signal=9, column='Close'):
+    macd_line = df[column].ewm(span=fast, adjust=False).mean() - df[column].ewm(span=slow, adjust=False).mean()
+    signal_line = macd_line.ewm(span=signal, adjust=False).mean()
+    return pd.DataFrame({'MACD': macd_line, 'Signal': signal_line, 'Histogram': macd_line - signal_line}, index=df.index)
+
+# Stochastic oscillator: %K is the close within the high-low range, %D its moving average
+def stochastic(df, k_window=14, d_window=3):
+    lowest = df['Low'].rolling(window=k_window, min_periods=k_window).min()
+    highest = df['High'].rolling(window=k_window, min_periods=k_window).max()
+    k = 100 * (df['Close'] - lowest) / (highest - lowest)
+    d = k.rolling(window=d_window, min_periods=d_window).mean()
+    return pd.DataFrame({'%K': k, '%D': d}, index=df.index)
+
+
+# Indicator catalogue used by the chart and the Historical Stock Data page
+# pane is 'price' for overlays on the candlesticks, otherwise the name of the sub-panel; levels are reference lines in that panel
+# needs_volume marks indicators that cannot be computed for indices, which have no volume
+INDICATORS = {
+    'SMA': {'function': sma, 'params': {'window': 20}, 'pane': 'price'},
+    'EMA': {'function': ema, 'params': {'window': 20}, 'pane': 'price'},
+    'Bollinger Bands': {'function': bollinger_bands, 'params': {'window': 20, 'num_std': 2.0}, 'pane': 'price'},
+    'VWAP': {'function': vwap, 'params': {}, 'pane': 'price', 'needs_volume': True},
+    'RSI': {'function': rsi, 'params': {'window': 14}, 'pane': 'RSI', 'levels': [30, 70]},
+    'MACD': {'function': macd, 'params': {'fast': 12, 'slow': 26, 'signal': 9}, 'pane': 'MACD'},
+    'Stochastic': {'function': stochastic, 'params': {'k_window': 14, 'd_window': 3}, 'pane': 'Stochastic', 'levels': [20, 80]},
+}
+
+# Compute an indicator from the catalogue as a DataFrame whose columns are labelled with the parameters, e.g. 'SMA(20)'
+def compute_indicator(df, name, **params):
+    spec = INDICATORS[name]
+    params = {**spec['params'], **params}
+    values = spec['function'](df, **params)
+    label = f"{name}({', '.join(str(value) for value in params.values())})" if params else name
+    if isinstance(values, pd.Series):
+        return values.to_frame(label)
+    return values.rename(columns=lambda column: f"{label} {column}")
+
+
+# Indicators Polygon also computes server-side (/v1/indicators), with the API name of each local parameter
+SERVER_INDICATORS = {
+    'SMA': {'window': 'window'},
+    'EMA': {'window': 'window'},
+    'RSI': {'window': 'window'},
+    'MACD': {'fast': 'short_window', 'slow': 'long_window', 'signal': 'signal_window'},
+}
+
+# Bars of history needed before the first compared value so that moving averages are full and smoothing has settled
+def warmup_bars(name, **params):
+    windows = [value for value in {**INDICATORS[name]['params'], **params}.values() if isinstance(value, int)]
+    return 10 * max(windows, default=1)
+
+# Compute an indicator on a price series ('close', 'open', 'high' or 'low') with the column names of the server-side values:
+# the indicator name for single series, 'MACD', 'Signal' and 'Histogram' for MACD
+def local_indicator(df, name, series_type='close', **params):
+    values = INDICATORS[name]['function'](df, column=series_type.capitalize(), **{**INDICATORS[name]['params'], **params})
+    return values.to_frame(name) if isinstance(values, pd.Series) else values
+
+# Compare server-side indicator values with the same indicator computed locally, on the dates of the server values
+# A value matches when the difference is within the tolerance relative to the server value (absolute for values below 1)
+def compare_indicator(server, local, tolerance=1e-3):
+    local = local.reindex(server.index)
+    report = pd.DataFrame(index=server.index)
+    match = pd.Series(True, index=server.index)
+    for column in server.columns:
+        difference = local[column] - server[column]
+        report[f"{column} Server"] = server[column]
+        report[f"{column} Local"] = local[column]
+        report[f"{column} Difference"] = difference
+        # Values missing locally count as discrepancies
+        match &= difference.abs() <= tolerance * server[column].abs().clip(lower=1)
+    report['Match'] = match
+    return report
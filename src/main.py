@@ -1,11 +1,24 @@
 import streamlit as st
 import streamlit_authenticator as sa
 import pandas as pd
-from datetime import datetime
-from polygon_api import get_historical_data_as_df, get_financials_as_df, create_financials_dataframe, get_company_details, get_stock_splits, get_dividends_data, get_news
-from chart import plot_candlestick_chart
-from config.display_config import display_data_with_default_sort, escape_markdown
+from datetime import datetime, date, time
+from polygon_api import get_historical_data_as_df, get_resampled_data_as_df, get_close_prices_as_df, get_financials_as_df, create_financials_dataframe, get_related_companies, get_peer_metrics_as_df, get_company_details, get_stock_splits, get_dividends_data, get_news, get_market_day_as_df, get_daily_open_close, get_snapshots_as_df, get_market_movers_as_df, get_trades_as_df, get_quotes_as_df, get_market_status, get_upcoming_holidays, get_market_holidays, search_tickers_as_df, refresh_ticker_universe, get_exchange_mics, get_option_expirations, get_option_chain_as_df, get_currency_conversion, validate_indicator
+from chart import plot_candlestick_chart, plot_line_chart, plot_correlation_heatmap, plot_spread_chart, plot_trade_size_histogram, plot_financial_trends, plot_peer_comparison, plot_valuation_history
+from comparison import rebased_returns, relative_strength, return_correlation, summarize_returns
+from indicators import INDICATORS, SERVER_INDICATORS
+from resample import RESAMPLE_INTERVALS, RESAMPLE_DEFAULT_DAYS, SESSIONS
+from market_day import top_movers
+from ticker_search import ticker_select, ticker_multiselect, benchmark_select, TICKER_TYPES, SEARCH_MARKETS
+from options import black_scholes_price, black_scholes_greeks, implied_volatility, fill_missing_greeks, chain_by_strike, years_to_expiry
+from streaming import PolygonStream, STREAM_CHANNELS
+from statements import STATEMENTS, TIMEFRAMES, statement_table
+from valuation import MULTIPLE_COLUMNS, DCF_INPUT_LABELS, DCF_FALLBACK_LABELS, DEFAULT_DISCOUNT_RATE, DEFAULT_TERMINAL_GROWTH, current_multiples, valuation_multiples, dcf_defaults, discounted_cash_flow, dcf_sensitivity, rate_range
+from financial_metrics import PEER_METRICS, DEFAULT_PEER_METRICS, LOWER_IS_BETTER, FREE_CASH_FLOW_NOTE, metrics_history, rank_peers
+from markets import MARKETS, VOLATILITY_INDICES, market_of, currency_pair
+from market_calendar import previous_trading_day, market_timestamp_ns, market_closed_reason
+from config.display_config import display_dataframe, display_data_with_default_sort, escape_markdown, format_number
 from authenticator import authenticate
+import config.cache_config as cache_config
 
 
 # Metadata
@@ -21,9 +34,9 @@ st.set_page_config(
     }
 )
 
-# Read envinmnet for Development
-API_KEY = st.secrets["API_KEY"]
-if API_KEY is None:
+# Read envinmnet for Development (the API key is not needed when serving from the offline cache)
+API_KEY = st.secrets.get("API_KEY")
+if API_KEY is None and not cache_config.OFFLINE_MODE:
     st.error("API_KEY is not set in .env file")
     st.stop()
 
@@ -48,32 +61,88 @@ elif st.session_state['authenticated']:
     if 'app_mode' not in st.session_state:
         st.session_state.app_mode = 'Select'
 
-# Sidebar to select the market data to view
+# Pages offered for each market; company data, ticks, options and corporate actions only exist for stocks
+MARKET_PAGES = {
+    'stocks': ['Select', 'Ticker Search', 'Company Detail', 'Market Snapshot', 'Live Chart', 'Index Dashboard', 'Historical Stock Data', 'Tick Data', 'Options Chain', 'Market Day', 'Company Financials Data', 'Financial Statements', 'Valuation', 'Stock Splits Data', 'Dividends Data'],
+    'fx': ['Select', 'Ticker Search', 'Market Snapshot', 'Live Chart', 'Historical Stock Data', 'Market Day', 'Currency Conversion'],
+    'crypto': ['Select', 'Ticker Search', 'Market Snapshot', 'Live Chart', 'Historical Stock Data', 'Market Day'],
+    'indices': ['Select', 'Ticker Search', 'Index Dashboard', 'Market Snapshot', 'Live Chart', 'Historical Stock Data'],
+}
+
+# Sidebar to select the market, then the market data to view
+market = MARKETS[st.sidebar.selectbox('Market', list(MARKETS), format_func=lambda name: MARKETS[name].label, key='market')]
 st.session_state.app_mode = st.sidebar.selectbox(
     'Choose the Market Data to View:',
-    ['Select', 'Company Detail', 'Historical Stock Data', 'Company Financials Data', 'Stock Splits Data', 'Dividends Data']
+    MARKET_PAGES[market.name],
+    format_func=lambda page: page if market.name == 'stocks' else page.replace('Stock Data', 'Data'),
+    key='page'
 )
 
+if cache_config.OFFLINE_MODE:
+    st.sidebar.info('Offline mode: data is served from the local cache only.')
+
+# Polygon allows one WebSocket connection per API key, so a live stream only runs while its page is open
+live_stream = st.session_state.get('live_stream')
+if live_stream is not None and st.session_state.app_mode != 'Live Chart':
+    if live_stream.running:
+        live_stream.stop()
+    del st.session_state['live_stream']
+
+# Market status banner with the next holiday or early close
+market_holidays = {}
+if st.session_state['authenticated']:
+    market_status = get_market_status(API_KEY)
+    if market_status and market.name == 'indices':
+        # Indices report a status per index family (S&P, Nasdaq, Dow Jones, CBOE...)
+        if 'open' in market_status.indices_groups.values():
+            st.sidebar.markdown(':green[●] **Indices open**')
+        else:
+            st.sidebar.markdown(':red[●] **Indices closed**')
+    elif market_status and market.name != 'stocks':
+        # Forex and crypto report their own status next to the stock market's
+        if market_status.currencies.get(market.name) == 'open':
+            st.sidebar.markdown(f':green[●] **{market.label} market open**')
+        else:
+            st.sidebar.markdown(f':red[●] **{market.label} market closed**')
+    elif market_status:
+        if market_status.market == 'open':
+            status = ':green[●] **Market open**'
+        elif market_status.market == 'extended-hours':
+            status = ':orange[●] **Pre-market trading**' if market_status.early_hours else ':orange[●] **After-hours trading**'
+        else:
+            status = ':red[●] **Market closed**'
+        st.sidebar.markdown(status)
+    upcoming_holidays = get_upcoming_holidays(API_KEY) if market.name == 'stocks' else []
+    if upcoming_holidays:
+        holiday = upcoming_holidays[0]
+        if holiday.status == 'early-close' and holiday.close:
+            closing_time = pd.Timestamp(holiday.close).tz_convert('US/Eastern').strftime('%H:%M')
+            st.sidebar.caption(f"Next: {holiday.name} on {holiday.date}, early close at {closing_time} ET")
+        else:
+            st.sidebar.caption(f"Next holiday: {holiday.name} on {holiday.date}")
+    # Closures announced by the exchanges, on top of the built-in holiday calendar
+    market_holidays = get_market_holidays(API_KEY)
+
 
 # Top-level header
 if st.session_state.app_mode == 'Select' and st.session_state['authenticated']:
     st.header('Latest News')
     # Get news data and display it
-    news_data = get_news()
+    news_data = get_news(API_KEY)
     # Display news data
     for news in news_data:
-        title = news.get('title', 'No Title Available')
-        description = news.get('description', 'No Summary Available')
-        author = news.get('author', 'Unknown Author')
-        published_date = news.get('published_utc', 'Unknown Date')
-        tickers = news.get('tickers', 'N/A')  # Tickers related to the news
+        title = news.title or 'No Title Available'
+        description = news.description or 'No Summary Available'
+        author = news.author or 'Unknown Author'
+        published_date = news.published_utc or 'Unknown Date'
+        tickers = news.tickers or 'N/A'  # Tickers related to the news
 
         # Convert tickers to a comma-separated string
         if isinstance(tickers, list):
             tickers = ', '.join(tickers)
 
-        article_url = news.get('article_url', '#')
-        image_url = news.get('image_url')  # URL of the image
+        article_url = news.article_url or '#'
+        image_url = news.image_url  # URL of the image
 
         # Escape $ in the description to avoid rendering as LaTeX
         escaped_description = escape_markdown(description)
@@ -92,45 +161,599 @@ if st.session_state.app_mode == 'Select' and st.session_state['authenticated']:
         st.write("---")
 
 
+# Market Snapshot: live quotes for a watchlist and the day's movers
+elif st.session_state.app_mode == 'Market Snapshot' and st.session_state['authenticated'] is True:
+    st.header("Market Snapshot")
+    watchlist = ticker_multiselect('Watchlist', API_KEY, list(market.watchlist), key='watchlist', market=market.name)
+    refresh_seconds = st.selectbox('Auto-refresh', options=[0, 5, 15, 30, 60], format_func=lambda seconds: f"Every {seconds} seconds" if seconds else 'Off')
+
+    # Rendered as a fragment so auto-refresh only reruns the tables, not the whole page
+    def show_market_snapshot():
+        try:
+            df = get_snapshots_as_df(watchlist, API_KEY, market.name)
+            missing = [symbol for symbol in watchlist if symbol not in df.index]
+            if missing:
+                st.warning(f"No snapshot found for: {', '.join(missing)}")
+            display_data_with_default_sort(df, 'Change (%)')
+
+            # There are no gainers and losers lists for indices
+            if market.snapshot_path:
+                st.subheader("Market Movers")
+                for tab, direction in zip(st.tabs(['Top Gainers', 'Top Losers']), ['gainers', 'losers']):
+                    with tab:
+                        movers = get_market_movers_as_df(direction, API_KEY, market.name)
+                        if movers.empty:
+                            st.info(f"No {direction} right now.")
+                        else:
+                            display_dataframe(movers)
+            st.caption(f"Last refreshed at {datetime.now().strftime('%H:%M:%S')}")
+        except Exception as e:
+            st.error(str(e))
+
+    if refresh_seconds:
+        st.experimental_fragment(show_market_snapshot, run_every=refresh_seconds)()
+    else:
+        show_market_snapshot()
+
+
+# Live Chart: bars streamed from Polygon's WebSocket feed into a rolling buffer, redrawn every second
+elif st.session_state.app_mode == 'Live Chart' and st.session_state['authenticated'] is True:
+    st.header("Live Chart")
+    ticker = ticker_select('Ticker', API_KEY, market.watchlist[0], key='live_ticker', market=market.name)
+    channels = STREAM_CHANNELS[market.name]
+    bar_sources = {'minute': 'Minute aggregates', 'second': 'Second aggregates', 'trades': 'Built from trades'}
+    bar_source = st.radio('Bars', [source for source in bar_sources if source in channels], format_func=bar_sources.get, horizontal=True)
+    # Trades are rolled up locally into bars of the chosen length
+    interval = st.selectbox('Bar length', [5, 15, 60], index=2, format_func=lambda seconds: f"{seconds} seconds") if bar_source == 'trades' else 60
+    max_bars = st.number_input('Bars kept', min_value=10, max_value=5000, value=500)
+
+    stream = st.session_state.get('live_stream')
+    if API_KEY is None:
+        st.info("Streaming needs an API key, so it is not available in offline mode.")
+    start_column, stop_column = st.columns(2)
+    if start_column.button('Start Streaming', disabled=API_KEY is None):
+        if stream is not None:
+            stream.stop()
+        # Trades and quotes also feed the last trade and quote shown above the chart
+        subscribed = [bar_source] + [channel for channel in ('trades', 'quotes') if channel in channels and channel != bar_source]
+        stream = PolygonStream(API_KEY, [ticker], subscribed, market=market.name, interval=interval, max_bars=max_bars).start()
+        st.session_state['live_stream'] = stream
+    if stop_column.button('Stop Streaming', disabled=stream is None or not stream.running):
+        stream.stop()
+
+    def show_live_chart():
+        stream = st.session_state.get('live_stream')
+        if stream is None:
+            st.info("Start streaming to chart live bars.")
+            return
+        status = f"Stream {stream.status}, {stream.events} events received"
+        if stream.error and stream.status != 'streaming':
+            status += f" (last error: {stream.error})"
+        st.caption(status)
+        for symbol in stream.tickers:
+            trade = stream.buffer.last_trades.get(symbol)
+            quote = stream.buffer.last_quotes.get(symbol)
+            last_column, bid_column, ask_column = st.columns(3)
+            last_column.metric(f"{symbol} Last Trade", format_number(trade.price) if trade else '-')
+            bid_column.metric('Bid', format_number(quote.bid_price) if quote else '-')
+            ask_column.metric('Ask', format_number(quote.ask_price) if quote else '-')
+            df = stream.buffer.to_frame(symbol)
+            if df.empty:
+                st.info(f"Waiting for the first {symbol} bar...")
+            else:
+                plot_candlestick_chart(df, extra_holidays=market_holidays, market=stream.market.name)
+
+    stream = st.session_state.get('live_stream')
+    if stream is not None and stream.running:
+        st.experimental_fragment(show_live_chart, run_every=1)()
+    else:
+        show_live_chart()
+
+
+# Index Dashboard: index levels and how the indices performed against each other
+elif st.session_state.app_mode == 'Index Dashboard' and st.session_state['authenticated'] is True:
+    st.header("Index Dashboard")
+    indices = ticker_multiselect('Indices', API_KEY, list(MARKETS['indices'].watchlist), key='dashboard_indices', market='indices')
+    if indices:
+        try:
+            snapshots = get_snapshots_as_df(indices, API_KEY, 'indices')
+            if not snapshots.empty:
+                for column, (symbol, snapshot) in zip(st.columns(len(snapshots)), snapshots.iterrows()):
+                    column.metric(symbol, format_number(snapshot['Last Trade']), f"{format_number(snapshot['Change (%)'])}%")
+            missing = [symbol for symbol in indices if symbol not in snapshots.index]
+            if missing:
+                st.warning(f"No snapshot found for: {', '.join(missing)}")
+        except Exception as e:
+            st.error(str(e))
+
+    from_date = st.date_input('From date', datetime(datetime.today().year, 1, 1), key='dashboard_from')
+    to_date = st.date_input('To date', datetime.today(), key='dashboard_to')
+    if st.button('Show Performance', disabled=not indices or from_date > to_date):
+        prices = get_close_prices_as_df(indices, from_date.strftime("%Y-%m-%d"), to_date.strftime("%Y-%m-%d"), False, 'day', API_KEY)
+        missing = [symbol for symbol in indices if symbol not in prices.columns]
+        if missing:
+            st.warning(f"No data found for: {', '.join(missing)}")
+        if len(prices) > 1:
+            # Volatility indices are levels of implied volatility, so they are plotted as is rather than as returns
+            volatility = [symbol for symbol in prices.columns if symbol in VOLATILITY_INDICES]
+            performance = prices.drop(columns=volatility)
+            if not performance.empty:
+                plot_line_chart(rebased_returns(performance), 'Index Returns', percent=True, reference=0, extra_holidays=market_holidays, market='indices')
+            if volatility:
+                plot_line_chart(prices[volatility], 'Volatility Indices', extra_holidays=market_holidays, market='indices')
+            display_data_with_default_sort(summarize_returns(prices), 'Total Return (%)')
+        else:
+            st.error("Not enough data to chart.")
+
+
 # Historical Stock Data
-elif st.session_state.app_mode is 'Historical Stock Data' and st.session_state['authenticated'] is True:
-    st.header("Historical Stock Data")
-    ticker = st.text_input('Enter ticker symbol', 'AAPL')
-    timespan = st.selectbox('Select timespan', options=['minute', 'hour', 'day', 'month', 'year'], index=2)  # Default to 'day'
-    from_date = st.date_input('From date', datetime(2022, 1, 1))
+elif st.session_state.app_mode == 'Historical Stock Data' and st.session_state['authenticated'] is True:
+    st.header("Historical Stock Data" if market.name == 'stocks' else f"Historical {market.label} Data")
+    comparison_mode = st.toggle('Compare multiple tickers', value=False)
+    if comparison_mode:
+        comparison_default = ['AAPL', 'MSFT', 'GOOGL'] if market.name == 'stocks' else [ticker for ticker in market.watchlist if ticker != market.benchmark]
+        selected_tickers = ticker_multiselect('Tickers to compare', API_KEY, comparison_default, key='comparison_tickers', market=market.name)
+        # Any ticker can be measured against an index such as I:SPX
+        benchmark = benchmark_select('Benchmark ticker', API_KEY, market.benchmark, key='benchmark', market=market.name)
+        resample = False
+    else:
+        ticker = ticker_select('Ticker', API_KEY, market.watchlist[0], key='historical_ticker', market=market.name)
+        # Resampling fetches minute bars once and builds every other interval from them locally
+        resample = st.radio('Bars', ['Polygon aggregates', 'Resampled from minute bars'], horizontal=True) == 'Resampled from minute bars'
+    if resample:
+        interval_column, session_column = st.columns(2)
+        interval = interval_column.selectbox('Interval', options=list(RESAMPLE_INTERVALS), index=1)  # Default to 5 minutes
+        if market.round_the_clock:
+            session = 'extended'  # Forex and crypto trade around the clock
+        else:
+            session = session_column.selectbox('Session', options=SESSIONS, format_func=lambda name: f"{name.capitalize()} hours")
+    else:
+        # Bar size is multiplier x timespan, e.g. 5 x minute for 5-minute bars
+        multiplier_column, timespan_column = st.columns(2)
+        multiplier = multiplier_column.number_input('Multiplier', min_value=1, max_value=1000, value=1)
+        timespan = timespan_column.selectbox('Select timespan', options=['second', 'minute', 'hour', 'day', 'week', 'month', 'quarter', 'year'], index=3)  # Default to 'day'
+    if resample:
+        # Minute bars add up quickly, so start from the last few trading days rather than years back
+        default_from = date.today()
+        for _ in range(RESAMPLE_DEFAULT_DAYS):
+            default_from = previous_trading_day(default_from, market_holidays, market.name)
+    else:
+        default_from = datetime(2022, 1, 1)
+    from_date = st.date_input('From date', default_from)
     to_date = st.date_input('To date', datetime.today())
-    adjusted = st.checkbox('Adjust for stock splits', value=True)  # checkbox default value is True for adjusted
-
-    if st.button('Get Historical Data'):
-        df = get_historical_data_as_df(ticker, from_date.strftime("%Y-%m-%d"), to_date.strftime("%Y-%m-%d"), adjusted, timespan, API_KEY)
-        if not df.empty:
-            # Plot candlestick chart
-            plot_candlestick_chart(df)
-            display_data_with_default_sort(df, 'Date')
+    valid_dates = from_date <= to_date
+    if not valid_dates:
+        st.error("The from date must not be after the to date.")
+    # Only stock prices are adjusted for splits
+    adjusted = st.checkbox('Adjust for stock splits', value=True) if market.split_adjusted else False  # checkbox default value is True for adjusted
+
+    if comparison_mode:
+        if st.button('Compare Tickers', disabled=not valid_dates):
+            tickers = list(selected_tickers)
+            # Fetch the benchmark alongside the tickers so every series shares the same dates
+            if benchmark and benchmark not in tickers:
+                tickers.append(benchmark)
+            prices = get_close_prices_as_df(tickers, from_date.strftime("%Y-%m-%d"), to_date.strftime("%Y-%m-%d"), adjusted, timespan, API_KEY, multiplier=multiplier)
+            missing = [symbol for symbol in tickers if symbol not in prices.columns]
+            if missing:
+                st.warning(f"No data found for: {', '.join(missing)}")
+            if len(prices) > 1 and len(prices.columns) > 1:
+                plot_line_chart(rebased_returns(prices), 'Cumulative Returns', percent=True, reference=0, extra_holidays=market_holidays, market=market.name)
+                if benchmark in prices.columns:
+                    plot_line_chart(relative_strength(prices, benchmark), f'Relative Strength vs {benchmark}', reference=1, extra_holidays=market_holidays, market=market.name)
+                plot_correlation_heatmap(return_correlation(prices))
+                display_data_with_default_sort(summarize_returns(prices), 'Total Return (%)')
+            else:
+                st.error("Not enough overlapping data to compare.")
+    else:
+        if not resample:
+            with st.expander("Aggregate Options", expanded=False):
+                sort = st.selectbox('Sort order', options=['asc', 'desc'], index=0)
+                limit = st.number_input('Maximum number of bars (0 = no limit)', min_value=0, max_value=50000, value=0)
+
+        # Technical indicators drawn on the chart, with their parameters
+        # Indices have no volume, so volume-based indicators are left out for them
+        indicator_names = [name for name, spec in INDICATORS.items() if not (spec.get('needs_volume') and market_of(ticker).name == 'indices')]
+        selected_indicators = st.multiselect('Technical indicators', indicator_names)
+        indicators = []
+        if selected_indicators:
+            with st.expander("Indicator Parameters", expanded=False):
+                for name in selected_indicators:
+                    params = {}
+                    for param, default in INDICATORS[name]['params'].items():
+                        min_value = 1 if isinstance(default, int) else 0.1
+                        params[param] = st.number_input(f"{name} {param.replace('_', ' ')}", min_value=min_value, value=default, key=f"indicator_{name}_{param}")
+                    indicators.append((name, params))
+        # Compare the indicators Polygon also computes server-side with the local computation
+        validate = st.checkbox("Cross-check indicators with Polygon's server-side values", value=False, key='validate_indicators',
+                               disabled=not any(name in SERVER_INDICATORS for name in selected_indicators))
+
+        # Keep the last query so indicators can be changed without fetching again
+        if st.button('Get Historical Data', disabled=not valid_dates):
+            query = {'ticker': ticker, 'from_date': from_date.strftime("%Y-%m-%d"), 'to_date': to_date.strftime("%Y-%m-%d"), 'adjusted': adjusted}
+            if resample:
+                query.update(interval=interval, session=session)
+            else:
+                query.update(timespan=timespan, multiplier=multiplier, sort=sort, limit=limit or None)
+            st.session_state['historical_query'] = query
+
+        if 'historical_query' in st.session_state:
+            query = st.session_state['historical_query']
+            if 'interval' in query:
+                df = get_resampled_data_as_df(api_key=API_KEY, **query)
+            else:
+                df = get_historical_data_as_df(api_key=API_KEY, **query)
+            if not df.empty:
+                # Plot candlestick chart
+                plot_candlestick_chart(df, indicators, extra_holidays=market_holidays, market=market_of(query['ticker']).name)
+                display_data_with_default_sort(df, 'Date')
+                if validate:
+                    if query.get('multiplier') != 1 or query.get('timespan') == 'second':
+                        st.info("Polygon's indicators are only available for Polygon aggregates of one minute or longer with a multiplier of 1.")
+                    else:
+                        st.subheader("Indicator Cross-Check")
+                        for name, params in indicators:
+                            if name not in SERVER_INDICATORS:
+                                continue
+                            try:
+                                report = validate_indicator(query['ticker'], name, query['from_date'], query['to_date'], query['adjusted'], query['timespan'], API_KEY, **params)
+                            except Exception as e:
+                                st.error(f"{name}: {e}")
+                                continue
+                            mismatches = report[~report['Match']]
+                            if report.empty:
+                                st.warning(f"{name}: Polygon returned no values.")
+                            elif mismatches.empty:
+                                st.success(f"{name}: all {len(report)} server values match the local computation.")
+                            else:
+                                st.warning(f"{name}: {len(mismatches)} of {len(report)} server values differ from the local computation.")
+                                display_dataframe(mismatches.drop(columns='Match'))
+            else:
+                st.error("No historical data found.")
+
+
+# Tick Data: individual trades and NBBO quotes within a time window
+elif st.session_state.app_mode == 'Tick Data' and st.session_state['authenticated'] is True:
+    st.header("Tick Data")
+    ticker = ticker_select('Ticker', API_KEY, key='tick_ticker')
+    day = st.date_input('Trading day', previous_trading_day(date.today(), market_holidays))
+    closed_reason = market_closed_reason(day, market_holidays)
+    if closed_reason:
+        st.warning(f"The market is closed on {day} ({closed_reason}).")
+    start_column, end_column = st.columns(2)
+    start_time = start_column.time_input('From time (US/Eastern)', time(9, 30))
+    end_time = end_column.time_input('To time (US/Eastern)', time(9, 35))
+    limit = st.number_input('Maximum number of trades and quotes (0 = no limit)', min_value=0, max_value=1000000, value=50000, step=10000)
+
+    if st.button('Get Tick Data'):
+        from_timestamp, to_timestamp = market_timestamp_ns(day, start_time), market_timestamp_ns(day, end_time)
+        if from_timestamp >= to_timestamp:
+            st.error("The start time must be before the end time.")
         else:
-            st.error("No historical data found.")
+            try:
+                trades = get_trades_as_df(ticker, from_timestamp, to_timestamp, API_KEY, limit or None)
+                quotes = get_quotes_as_df(ticker, from_timestamp, to_timestamp, API_KEY, limit or None)
+                if trades.empty:
+                    st.error("No trades found.")
+                else:
+                    st.subheader("Trades")
+                    columns = st.columns(3)
+                    columns[0].metric('Trades', format_number(len(trades), 0))
+                    columns[1].metric('Shares', format_number(trades['Size'].sum(), 0))
+                    columns[2].metric('VWAP', format_number((trades['Price'] * trades['Size']).sum() / trades['Size'].sum(), 4))
+                    plot_trade_size_histogram(trades)
+                    display_data_with_default_sort(trades, 'Time')
+                if not quotes.empty:
+                    st.subheader("Quotes")
+                    plot_spread_chart(quotes)
+                    display_data_with_default_sort(quotes, 'Time')
+            except Exception as e:
+                st.error(str(e))
+
+
+# Options Chain: calls and puts of one expiration side by side, with greeks filled in by Black-Scholes where missing
+elif st.session_state.app_mode == 'Options Chain' and st.session_state['authenticated'] is True:
+    st.header("Options Chain")
+    underlying = ticker_select('Underlying ticker', API_KEY, key='options_underlying')
+    try:
+        expirations = get_option_expirations(underlying, API_KEY)
+    except Exception as e:
+        st.error(str(e))
+        expirations = []
+    if not expirations:
+        st.warning(f"No options contracts found for {underlying}.")
+    else:
+        expiration = st.selectbox('Expiration', expirations, key='options_expiration')
+        with st.expander("Black-Scholes Inputs", expanded=False):
+            rate = st.number_input('Risk-free rate (%)', min_value=0.0, max_value=20.0, value=5.0, step=0.25, key='options_rate')
+            dividend_yield = st.number_input('Dividend yield (%)', min_value=0.0, max_value=20.0, value=0.0, step=0.25, key='options_dividend_yield')
+
+        if st.button('Get Options Chain'):
+            try:
+                chain = get_option_chain_as_df(underlying, expiration, API_KEY)
+                if chain.empty:
+                    st.error(f"No options found for {underlying} expiring {expiration}.")
+                else:
+                    underlying_prices = chain['Underlying Price'].dropna()
+                    if underlying_prices.empty:
+                        st.warning("The underlying price is not available, greeks cannot be computed locally.")
+                    else:
+                        updated = chain['Underlying Updated'].dropna()
+                        as_of = updated.max() if not updated.empty else pd.Timestamp.now(tz='US/Eastern')
+                        chain = fill_missing_greeks(chain, underlying_prices.iloc[0], rate / 100, as_of, dividend_yield / 100)
+                        columns = st.columns(3)
+                        columns[0].metric('Underlying Price', format_number(underlying_prices.iloc[0]))
+                        columns[1].metric('Days to Expiry', format_number(years_to_expiry(expiration, as_of) * 365, 1))
+                        columns[2].metric('Contracts', format_number(len(chain), 0))
+                        computed = int(chain['Computed'].sum())
+                        if computed:
+                            st.caption(f"Implied volatility or greeks of {computed} contracts were computed locally with Black-Scholes.")
+                    display_dataframe(chain_by_strike(chain))
+                    with st.expander("All Contracts", expanded=False):
+                        display_dataframe(chain.drop(columns=['Underlying Price', 'Underlying Updated']))
+            except Exception as e:
+                st.error(str(e))
+
+    # Black-Scholes calculator for any contract, e.g. one the API has no greeks for
+    st.subheader("Black-Scholes Calculator")
+    columns = st.columns(3)
+    contract_type = columns[0].radio('Type', ['call', 'put'], horizontal=True, key='calculator_type')
+    spot = columns[1].number_input('Underlying price', min_value=0.01, value=100.0, key='calculator_spot')
+    strike = columns[2].number_input('Strike', min_value=0.01, value=100.0, key='calculator_strike')
+    columns = st.columns(4)
+    days = columns[0].number_input('Days to expiry', min_value=0.0, value=30.0, key='calculator_days')
+    volatility = columns[1].number_input('Volatility (%)', min_value=0.0, max_value=500.0, value=25.0, key='calculator_volatility')
+    calculator_rate = columns[2].number_input('Risk-free rate (%)', min_value=0.0, max_value=20.0, value=5.0, key='calculator_rate')
+    calculator_dividend = columns[3].number_input('Dividend yield (%)', min_value=0.0, max_value=20.0, value=0.0, key='calculator_dividend_yield')
+    market_price = st.number_input('Market price (optional, to solve the implied volatility)', min_value=0.0, value=0.0, key='calculator_market_price')
+
+    years = days / 365
+    price = black_scholes_price(spot, strike, years, calculator_rate / 100, volatility / 100, contract_type, calculator_dividend / 100)
+    greeks = black_scholes_greeks(spot, strike, years, calculator_rate / 100, volatility / 100, contract_type, calculator_dividend / 100)
+    columns = st.columns(3)
+    columns[0].metric('Theoretical Price', format_number(price, 4))
+    columns[1].metric('Delta', format_number(greeks['delta'], 4) or 'N/A')
+    columns[2].metric('Gamma', format_number(greeks['gamma'], 4) or 'N/A')
+    columns = st.columns(3)
+    columns[0].metric('Theta (per day)', format_number(greeks['theta'], 4) or 'N/A')
+    columns[1].metric('Vega (per 1%)', format_number(greeks['vega'], 4) or 'N/A')
+    columns[2].metric('Rho (per 1%)', format_number(greeks['rho'], 4) or 'N/A')
+    if market_price > 0:
+        solved = implied_volatility(market_price, spot, strike, years, calculator_rate / 100, contract_type, calculator_dividend / 100)
+        if solved is None:
+            st.warning("No volatility reproduces this market price.")
+        else:
+            st.metric('Implied Volatility', f"{format_number(solved * 100)}%")
+
+
+# Market Day: the whole market's daily bars from the grouped daily endpoint
+elif st.session_state.app_mode == 'Market Day' and st.session_state['authenticated'] is True:
+    st.header("Market Day")
+    day = st.date_input('Trading day', previous_trading_day(date.today(), market_holidays, market.name))
+    closed_reason = market_closed_reason(day, market_holidays, market.name)
+    if closed_reason:
+        st.warning(f"The market is closed on {day} ({closed_reason}).")
+    adjusted = st.checkbox('Adjust for stock splits', value=True) if market.split_adjusted else False
+    with st.expander("Mover Filters", expanded=False):
+        count = st.number_input('Tickers per list', min_value=1, max_value=100, value=10)
+        min_price = st.number_input('Minimum close price', min_value=0.0, value=1.0)
+        min_volume = st.number_input('Minimum volume', min_value=0, value=100000 if market.name == 'stocks' else 0, step=10000)
+    # The daily open/close endpoint only covers stocks
+    ticker = ticker_select('Ticker for the daily open/close', API_KEY, key='open_close_ticker') if market.name == 'stocks' else None
+
+    if st.button('Get Market Day'):
+        df = get_market_day_as_df(day.strftime("%Y-%m-%d"), adjusted, API_KEY, market.name)
+        if df.empty:
+            st.error(f"No market data found for {day}. The market may have been closed.")
+        else:
+            st.caption(f"{len(df)} tickers traded on {day}")
+            movers = top_movers(df, count, min_price, min_volume)
+            for tab, (name, movers_df) in zip(st.tabs(list(movers)), movers.items()):
+                with tab:
+                    if movers_df.empty:
+                        st.info(f"No tickers in {name}.")
+                    else:
+                        display_dataframe(movers_df)
+
+        # Daily open/close card, including pre-market and after-hours prices
+        if ticker:
+            st.subheader(f"Daily Open/Close for {ticker}")
+            open_close = get_daily_open_close(ticker, day.strftime("%Y-%m-%d"), adjusted, API_KEY)
+            if open_close is None:
+                st.warning(f"No open/close data found for {ticker} on {day}.")
+            else:
+                previous_close = df['Previous Close'].get(ticker) if not df.empty else None
+                close_delta = format_number(open_close.close - previous_close) if previous_close is not None and pd.notna(previous_close) else None
+                after_hours_delta = format_number(open_close.after_hours - open_close.close) if open_close.after_hours is not None else None
+                columns = st.columns(4)
+                columns[0].metric('Pre-Market', format_number(open_close.pre_market) or 'N/A')
+                columns[1].metric('Open', format_number(open_close.open))
+                columns[2].metric('Close', format_number(open_close.close), close_delta)
+                columns[3].metric('After Hours', format_number(open_close.after_hours) or 'N/A', after_hours_delta)
+                columns = st.columns(4)
+                columns[0].metric('High', format_number(open_close.high))
+                columns[1].metric('Low', format_number(open_close.low))
+                columns[2].metric('Volume', format_number(open_close.volume, 0))
+
+
+# Currency Conversion: convert an amount at the last forex quote
+elif st.session_state.app_mode == 'Currency Conversion' and st.session_state['authenticated'] is True:
+    st.header("Currency Conversion")
+    pair = ticker_select('Currency pair', API_KEY, 'C:EURUSD', key='conversion_pair', market='fx')
+    amount = st.number_input('Amount', min_value=0.0, value=1000.0, step=100.0)
+    inverse = st.toggle('Convert the other way', value=False)
+
+    if st.button('Convert'):
+        from_currency, to_currency = currency_pair(pair)
+        if inverse:
+            from_currency, to_currency = to_currency, from_currency
+        try:
+            conversion = get_currency_conversion(from_currency, to_currency, amount, API_KEY)
+            columns = st.columns(3)
+            columns[0].metric(f"{format_number(conversion.initial_amount)} {conversion.from_currency}", f"{format_number(conversion.converted)} {conversion.to_currency}")
+            columns[1].metric('Bid', format_number(conversion.bid, 5) or 'N/A')
+            columns[2].metric('Ask', format_number(conversion.ask, 5) or 'N/A')
+            if conversion.timestamp:
+                quoted_at = pd.Timestamp(conversion.timestamp, unit='ms', tz='UTC').tz_convert('US/Eastern')
+                st.caption(f"Quoted at {quoted_at.strftime('%Y-%m-%d %H:%M:%S')} ET")
+        except Exception as e:
+            st.error(str(e))
 
 
 # Financials Data
-elif st.session_state.app_mode is 'Company Financials Data' and st.session_state['authenticated'] is True:
+elif st.session_state.app_mode == 'Company Financials Data' and st.session_state['authenticated'] is True:
     st.header("Company Financials Data")
-    ticker = st.text_input('Enter ticker symbol', 'AAPL')
-    limit = st.number_input('Enter the number of financial records to retrieve (min=1, max=100)', min_value=1, max_value=100, value=30) # Default to 30
-    # Dropdown for timeframe
-    timeframe = st.selectbox('Select timeframe', options=['', 'annual', 'quarterly', 'ttm'], index=0)
+    peer_mode = st.toggle('Compare with peers', value=False, key='peer_mode')
+    if peer_mode:
+        ticker = ticker_select('Company', API_KEY, key='peer_ticker')
+        # Polygon's related companies seed the peers, which follow the company until edited
+        suggest = st.checkbox('Suggest related companies', value=True, key='peer_related')
+        related = [peer for peer in get_related_companies(ticker, API_KEY) if peer != ticker][:5] if suggest else []
+        peers = ticker_multiselect('Peers', API_KEY, related, key=f"peers_{ticker}" if suggest else 'peers')
+        period = st.radio('Periods', options=list(TIMEFRAMES), horizontal=True, key='peer_timeframe')
+        ranked_metrics = st.multiselect('Rank on', PEER_METRICS, default=DEFAULT_PEER_METRICS, key='peer_metrics')
+
+        if st.button('Compare Peers', disabled=not ranked_metrics):
+            tickers = [ticker] + [peer for peer in peers if peer != ticker]
+            table = get_peer_metrics_as_df(tickers, TIMEFRAMES[period], API_KEY)
+            missing = [symbol for symbol in tickers if symbol not in table.index]
+            if missing:
+                st.warning(f"No financials found for: {', '.join(missing)}")
+            if table.empty:
+                st.error("No data found.")
+            else:
+                ranking = rank_peers(table, ranked_metrics)
+                display_dataframe(pd.concat([table.loc[ranking.index, ['Period']], ranking], axis=1))
+                plot_peer_comparison(table, ranked_metrics, LOWER_IS_BETTER)
+    else:
+        ticker = ticker_select('Ticker', API_KEY, key='financials_ticker')
+        limit = st.number_input('Enter the number of financial records to retrieve (min=1, max=1000)', min_value=1, max_value=1000, value=30) # Default to 30
+        # Dropdown for timeframe
+        timeframe = st.selectbox('Select timeframe', options=['', 'annual', 'quarterly', 'ttm'], index=0, key='financials_timeframe')
+
+        if st.button('Get Financials'):
+            # Pass None if the selected option is 'None'
+            timeframe_to_pass = None if timeframe == '' else timeframe
+            financials_data = get_financials_as_df(ticker, limit, API_KEY, timeframe=timeframe_to_pass)
+            df_financials = create_financials_dataframe(financials_data)
+            display_data_with_default_sort(df_financials, 'End Date')
+            st.caption(FREE_CASH_FLOW_NOTE)
+            # Trends only make sense over reports of one timeframe
+            if timeframe_to_pass is None:
+                st.info("Select a timeframe to chart revenue, earnings, margins and cash flow over time.")
+            elif financials_data:
+                plot_financial_trends(metrics_history(financials_data))
+
+
+# Financial Statements: income statement, balance sheet and cash flow statement with periods as columns
+elif st.session_state.app_mode == 'Financial Statements' and st.session_state['authenticated'] is True:
+    st.header("Financial Statements")
+    ticker = ticker_select('Ticker', API_KEY, key='statements_ticker')
+    period_column, count_column = st.columns(2)
+    period = period_column.radio('Periods', options=list(TIMEFRAMES), horizontal=True, key='statements_timeframe')
+    count = count_column.number_input('Number of periods', min_value=1, max_value=40, value=8, key='statements_periods')
+    detailed = st.toggle('Show all line items', value=False, key='statements_detailed')
+
+    # Keep the last query so line items can be expanded without fetching again
+    if st.button('Get Statements'):
+        st.session_state['statements_query'] = {'ticker': ticker, 'limit': count, 'timeframe': TIMEFRAMES[period]}
+
+    if 'statements_query' in st.session_state:
+        query = st.session_state['statements_query']
+        reports = get_financials_as_df(query['ticker'], query['limit'], API_KEY, timeframe=query['timeframe'])
+        if not reports:
+            st.error("No financial statements found.")
+        else:
+            st.caption(f"{reports[0].company_name or query['ticker']}, {len(reports)} reports")
+            for statement, title in STATEMENTS.items():
+                table = statement_table(reports, statement, detailed=detailed)
+                if table.empty:
+                    continue
+                with st.expander(title, expanded=statement != 'comprehensive_income'):
+                    display_dataframe(table)
+
+
+# Valuation: price multiples over time from the filings and closes, and a discounted cash flow model
+elif st.session_state.app_mode == 'Valuation' and st.session_state['authenticated'] is True:
+    st.header("Valuation")
+    ticker = ticker_select('Ticker', API_KEY, key='valuation_ticker')
+    period_column, years_column = st.columns(2)
+    # Multiples need a year of results per report
+    period = period_column.radio('Fundamentals', options=['TTM', 'Annual'], horizontal=True, key='valuation_timeframe')
+    history_years = years_column.number_input('Years of history', min_value=1, max_value=20, value=5, key='valuation_years')
+
+    # Keep the last query so the DCF assumptions can be changed without fetching again
+    if st.button('Get Valuation'):
+        st.session_state['valuation_query'] = {'ticker': ticker, 'timeframe': TIMEFRAMES[period], 'years': history_years}
+
+    if 'valuation_query' in st.session_state:
+        query = st.session_state['valuation_query']
+        # A TTM report comes out every quarter
+        limit = query['years'] * (4 if query['timeframe'] == 'ttm' else 1) + 1
+        reports = get_financials_as_df(query['ticker'], limit, API_KEY, timeframe=query['timeframe'])
+        # Prices are not split-adjusted so they match the per-share figures of each filing
+        from_date = (pd.Timestamp(date.today()) - pd.DateOffset(years=query['years'] + 1)).strftime("%Y-%m-%d")
+        prices = get_historical_data_as_df(query['ticker'], from_date, date.today().strftime("%Y-%m-%d"), False, 'day', API_KEY)
+        try:
+            dividends = get_dividends_data(query['ticker'], 4 * query['years'] + 8, API_KEY)
+        except Exception:
+            dividends = []
+            st.warning("Dividends could not be retrieved, so the dividend yield is left out.")
+
+        if not reports:
+            st.error("No financial reports found.")
+        elif prices.empty:
+            st.error("No price data found.")
+        else:
+            current = current_multiples(reports, prices['Close'], dividends)
+            st.caption(f"Last close {format_number(current['Price'])} on {prices.index.max():%Y-%m-%d} against the {current['Period']} report")
+            for column, name in zip(st.columns(len(MULTIPLE_COLUMNS)), MULTIPLE_COLUMNS):
+                column.metric(name, format_number(current[name]) or 'N/A')
+            multiples = valuation_multiples(reports, prices['Close'], dividends)
+            plot_valuation_history(multiples)
+            display_data_with_default_sort(multiples, 'End Date')
+            st.caption(FREE_CASH_FLOW_NOTE)
+
+            st.subheader("Discounted Cash Flow")
+            defaults = dcf_defaults(reports)
+            if defaults['estimated']:
+                st.caption("Not available from the filings, so estimated: " + ', '.join(f"{DCF_INPUT_LABELS[name]} ({DCF_FALLBACK_LABELS[name]})" for name in defaults['estimated']) + '.')
+            # Keys follow the company so its own figures are the starting point
+            suffix = f"{query['ticker']}_{query['timeframe']}"
+            growth_column, margin_column, projection_column = st.columns(3)
+            growth = growth_column.number_input('Revenue growth (% a year)', value=round(defaults['growth'] * 100, 2), step=0.5, key=f"dcf_growth_{suffix}") / 100
+            fcf_margin = margin_column.number_input('Free cash flow margin (%)', value=round(0.0 if pd.isna(defaults['fcf_margin']) else defaults['fcf_margin'] * 100, 2),
+                                                    step=0.5, key=f"dcf_margin_{suffix}") / 100
+            projection_years = projection_column.number_input('Projection years', min_value=1, max_value=30, value=5, key=f"dcf_years_{suffix}")
+            discount_column, terminal_column, debt_column = st.columns(3)
+            discount_rate = discount_column.number_input('Discount rate (%)', value=DEFAULT_DISCOUNT_RATE * 100, step=0.5, key=f"dcf_discount_rate_{suffix}") / 100
+            terminal_growth = terminal_column.number_input('Terminal growth (%)', value=DEFAULT_TERMINAL_GROWTH * 100, step=0.25, key=f"dcf_terminal_growth_{suffix}") / 100
+            net_debt = debt_column.number_input('Net debt', value=float(defaults['net_debt']), step=1e9, key=f"dcf_net_debt_{suffix}")
+
+            if pd.isna(defaults['revenue']) or pd.isna(defaults['shares']):
+                st.error("The latest report has no revenue or share count to value.")
+            elif discount_rate <= terminal_growth:
+                st.error("The discount rate must be higher than the terminal growth rate.")
+            else:
+                projection, summary = discounted_cash_flow(defaults['revenue'], growth, fcf_margin, discount_rate, terminal_growth, projection_years, net_debt, defaults['shares'])
+                upside = summary['Value per Share'] / current['Price'] - 1
+                value_column, price_column, upside_column = st.columns(3)
+                value_column.metric('Value per Share', format_number(summary['Value per Share']))
+                price_column.metric('Last Close', format_number(current['Price']))
+                upside_column.metric('Upside (%)', format_number(upside * 100))
+                display_dataframe(projection)
+                display_dataframe(pd.Series(summary, name='Value').to_frame())
 
-    if st.button('Get Financials'):
-        # Pass None if the selected option is 'None'
-        timeframe_to_pass = None if timeframe == '' else timeframe
-        financials_data = get_financials_as_df(ticker, limit, API_KEY, timeframe=timeframe_to_pass)
-        df_financials = create_financials_dataframe(financials_data)
-        display_data_with_default_sort(df_financials, 'End Date')
+                st.markdown("**Value per share by discount rate and terminal growth**")
+                sensitivity = dcf_sensitivity(defaults['revenue'], growth, fcf_margin, rate_range(discount_rate, 0.01), rate_range(terminal_growth, 0.005),
+                                              projection_years, net_debt, defaults['shares'])
+                display_dataframe(sensitivity.rename(index=lambda rate: f"{rate:.1%}", columns=lambda rate: f"{rate:.2%}"))
 
 
 # Company Detail
-elif st.session_state.app_mode is 'Company Detail' and st.session_state['authenticated'] is True:
+elif st.session_state.app_mode == 'Company Detail' and st.session_state['authenticated'] is True:
     st.header("Company Detail")
-    ticker = st.text_input('Enter ticker symbol', 'AAPL').upper()
+    ticker = ticker_select('Ticker', API_KEY, key='company_ticker')
     
     if st.button('Get Company Details'):
         try:
@@ -177,15 +800,15 @@ elif st.session_state.app_mode is 'Company Detail' and st.session_state['authent
                 st.write(company_details_df.to_html(escape=False, index=False), unsafe_allow_html=True)
 
                 # Fetch and display related news
-                related_news = get_news(ticker=ticker)
+                related_news = get_news(API_KEY, ticker=ticker)
                 st.subheader(f"Related News for {ticker}")
                 for news_item in related_news[:3]:  # Display only the first 3 related news items
-                    title = news_item.get('title', 'No Title Available')
-                    description = news_item.get('description', 'No Summary Available')
-                    author = news_item.get('author', 'Unknown Author')
-                    published_date = news_item.get('published_utc', 'Unknown Date')
-                    article_url = news_item.get('article_url', '#')
-                    image_url = news_item.get('image_url')
+                    title = news_item.title or 'No Title Available'
+                    description = news_item.description or 'No Summary Available'
+                    author = news_item.author or 'Unknown Author'
+                    published_date = news_item.published_utc or 'Unknown Date'
+                    article_url = news_item.article_url or '#'
+                    image_url = news_item.image_url
 
                     # Escape $ in the description to avoid rendering as LaTeX
                     escaped_description = escape_markdown(description)
@@ -201,10 +824,40 @@ elif st.session_state.app_mode is 'Company Detail' and st.session_state['authent
         except Exception as e:
             st.error(str(e))
 
+# Ticker Search
+elif st.session_state.app_mode == 'Ticker Search' and st.session_state['authenticated'] is True:
+    st.header("Ticker Search")
+    search = st.text_input('Search by ticker or company name', 'Apple')
+    market_column, type_column, exchange_column = st.columns(3)
+    search_market = market_column.selectbox('Market', SEARCH_MARKETS, index=SEARCH_MARKETS.index(market.name))
+    ticker_type = type_column.selectbox('Type', [''] + list(TICKER_TYPES), key='search_type', format_func=lambda code: TICKER_TYPES.get(code, 'Any type'))
+    exchange = exchange_column.selectbox('Primary exchange', [''] + get_exchange_mics(API_KEY), format_func=lambda mic: mic or 'Any exchange')
+    active = st.checkbox('Active tickers only', value=True)
+    limit = st.number_input('Limit', min_value=1, max_value=1000, value=100, step=1)
+
+    if st.button('Search Tickers'):
+        try:
+            df_tickers = search_tickers_as_df(search.strip(), search_market, ticker_type, exchange, active, limit, API_KEY)
+            if df_tickers.empty:
+                st.error("No tickers found.")
+            else:
+                display_dataframe(df_tickers)
+        except Exception as e:
+            st.error(str(e))
+
+    # The ticker pickers on every page use a locally cached list, which can be reloaded here
+    st.subheader("Ticker List")
+    if st.button('Refresh Ticker List'):
+        try:
+            st.success(f"Loaded {refresh_ticker_universe(API_KEY, search_market):,} {search_market} tickers.")
+        except Exception as e:
+            st.error(str(e))
+
+
 # Stock Splits Data
-elif st.session_state.app_mode is 'Stock Splits Data' and st.session_state['authenticated'] is True:
+elif st.session_state.app_mode == 'Stock Splits Data' and st.session_state['authenticated'] is True:
     st.header("Stock Splits Data")
-    ticker = st.text_input('Enter ticker symbol (optional)')
+    ticker = ticker_select('Ticker (optional)', API_KEY, default='', key='splits_ticker', optional=True)
 
     # execution_date filters
     with st.expander("Execution Date Filters", expanded=False):  # expanded=False to collapse the expander by default
@@ -225,13 +878,13 @@ elif st.session_state.app_mode is 'Stock Splits Data' and st.session_state['auth
     if st.button('Get Stock Splits'):
         # Create a dictionary of date filters
         date_filters = {'gt': gt, 'gte': gte, 'lt': lt, 'lte': lte}
-        df_splits = get_stock_splits(ticker, limit, **date_filters)
+        df_splits = get_stock_splits(ticker, limit, API_KEY, **date_filters)
         display_data_with_default_sort(df_splits, 'Execution Date')
 
 # Dividends Data
-elif st.session_state.app_mode is 'Dividends Data' and st.session_state['authenticated'] is True:
+elif st.session_state.app_mode == 'Dividends Data' and st.session_state['authenticated'] is True:
     st.header("Dividends Data")
-    ticker = st.text_input('Enter ticker symbol', 'AAPL').upper()
+    ticker = ticker_select('Ticker', API_KEY, key='dividends_ticker')
     limit = st.number_input('Limit', min_value=1, max_value=1000, value=50, step=1)
 
     if st.button('Get Dividends'):
@@ -255,6 +908,8 @@ elif st.session_state.app_mode is 'Dividends Data' and st.session_state['authent
             # Reorder columns
             columns_order = ['Ticker', 'Declaration Date', 'Ex Dividend Date', 'Record Date', 'Pay Date', 'Frequency', 'Type', 'Amount']
             df_dividends = df_dividends[columns_order]
+            for col in ['Declaration Date', 'Ex Dividend Date', 'Record Date', 'Pay Date']:
+                df_dividends[col] = pd.to_datetime(df_dividends[col])
 
             # Use the display_data_with_default_sort function to display the DataFrame sorted by 'Declaration Date'
             display_data_with_default_sort(df_dividends, 'Declaration Date')
@@ -0,0 +1,104 @@
+from datetime import date, timedelta
+import pandas as pd
+
+# US equity market calendar (NYSE/Nasdaq full-day holidays), used to remove non-trading gaps from charts
+# Forex trades around the clock on weekdays and crypto every day, so neither follows the holiday calendar
+
+# Easter Sunday for a year (anonymous Gregorian algorithm)
+def easter_sunday(year):
+    a = year % 19
+    b, c = divmod(year, 100)
+    d, e = divmod(b, 4)
+    f = (b + 8) // 25
+    g = (b - f + 1) // 3
+    h = (19 * a + b - d - g + 15) % 30
+    i, k = divmod(c, 4)
+    l = (32 + 2 * e + 2 * i - h - k) % 7
+    m = (a + 11 * h + 22 * l) // 451
+    month, day = divmod(h + l - 7 * m + 114, 31)
+    return date(year, month, day + 1)
+
+# The n-th given weekday (Monday=0) of a month; n=-1 is the last one
+def nth_weekday(year, month, weekday, n):
+    if n > 0:
+        first = date(year, month, 1)
+        return first + timedelta(days=(weekday - first.weekday()) % 7 + 7 * (n - 1))
+    last = date(year + (month == 12), month % 12 + 1, 1) - timedelta(days=1)
+    return last - timedelta(days=(last.weekday() - weekday) % 7)
+
+# Holidays on a Saturday are observed on Friday, on a Sunday on Monday
+def observed(day):
+    if day.weekday() == 5:
+        return day - timedelta(days=1)
+    if day.weekday() == 6:
+        return day + timedelta(days=1)
+    return day
+
+# Full-day market holidays for a year
+def us_market_holidays(year):
+    holidays = {
+        "New Year's Day": date(year, 1, 1),
+        'Martin Luther King Jr. Day': nth_weekday(year, 1, 0, 3),
+        "Washington's Birthday": nth_weekday(year, 2, 0, 3),
+        'Good Friday': easter_sunday(year) - timedelta(days=2),
+        'Memorial Day': nth_weekday(year, 5, 0, -1),
+        'Independence Day': observed(date(year, 7, 4)),
+        'Labor Day': nth_weekday(year, 9, 0, 1),
+        'Thanksgiving Day': nth_weekday(year, 11, 3, 4),
+        'Christmas Day': observed(date(year, 12, 25)),
+    }
+    # New Year's Day falling on a Saturday is not observed on the previous Friday
+    if date(year, 1, 1).weekday() == 5:
+        del holidays["New Year's Day"]
+    if year >= 2022:
+        holidays['Juneteenth'] = observed(date(year, 6, 19))
+    return {day: name for name, day in holidays.items() if day.weekday() < 5}
+
+# Full-day market holidays between two dates (inclusive), plus extra closures such as those announced by the exchanges
+def holidays_between(start, end, extra_holidays=None):
+    days = dict(extra_holidays or {})
+    for year in range(start.year, end.year + 1):
+        days.update(us_market_holidays(year))
+    return sorted(day for day in days if start <= day <= end)
+
+# Why a market ('stocks', 'fx' or 'crypto') is closed on a day ('Weekend' or the holiday name), or None on a trading day
+def market_closed_reason(day, extra_holidays=None, market='stocks'):
+    if market == 'crypto':
+        return None
+    if day.weekday() >= 5:
+        return 'Weekend'
+    if market == 'fx':
+        return None
+    return us_market_holidays(day.year).get(day) or (extra_holidays or {}).get(day)
+
+# The last trading day before a date, skipping weekends and full-day holidays
+def previous_trading_day(day, extra_holidays=None, market='stocks'):
+    day = date.fromisoformat(day) if isinstance(day, str) else day
+    day -= timedelta(days=1)
+    while market_closed_reason(day, extra_holidays, market):
+        day -= timedelta(days=1)
+    return day
+
+# Unix nanoseconds of a date and time of day in US/Eastern, as used by the trades and quotes endpoints
+def market_timestamp_ns(day, time_of_day):
+    return pd.Timestamp.combine(day, time_of_day).tz_localize('US/Eastern').value
+
+# Plotly rangebreaks hiding weekends, holidays (plus extra closures) and, for intraday bars, hours outside the extended session
+# Crypto charts have no gaps; forex charts only hide weekends (Saturdays for intraday bars, trading resumes on Sunday evening)
+def get_rangebreaks(index, extra_holidays=None, market='stocks'):
+    if len(index) < 2 or market == 'crypto':
+        return []
+    spacing = pd.Series(index).diff().median()
+    # Weekly and longer bars have no gaps worth hiding
+    if spacing >= pd.Timedelta(days=7):
+        return []
+    if market == 'fx':
+        return [dict(bounds=['sat', 'sun'] if spacing < pd.Timedelta(days=1) else ['sat', 'mon'])]
+    rangebreaks = [dict(bounds=['sat', 'mon'])]
+    holidays = holidays_between(index.min().date(), index.max().date(), extra_holidays)
+    if holidays:
+        rangebreaks.append(dict(values=[day.isoformat() for day in holidays]))
+    if spacing < pd.Timedelta(days=1):
+        # Extended trading hours run from 4:00 to 20:00 US/Eastern
+        rangebreaks.append(dict(bounds=[20, 4], pattern='hour'))
+    return rangebreaks
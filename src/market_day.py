@@ -0,0 +1,22 @@
+import pandas as pd
+
+# Whole-market daily views built from grouped daily bars (one row per ticker, indexed by 'Ticker')
+
+# Add the previous close and the day's change; tickers missing on the previous day get no change
+def add_daily_change(bars, previous_bars):
+    df = bars.copy()
+    previous_close = previous_bars['Close'] if not previous_bars.empty else pd.Series(dtype='float64')
+    df['Previous Close'] = previous_close.reindex(df.index)
+    df['Change'] = df['Close'] - df['Previous Close']
+    df['Change (%)'] = df['Change'] / df['Previous Close'] * 100
+    return df
+
+# Top gainers, top losers and most active tickers, ignoring tickers below a price or volume floor (e.g. penny stocks)
+def top_movers(df, count=10, min_price=0.0, min_volume=0):
+    df = df[(df['Close'] >= min_price) & (df['Volume'] >= min_volume)]
+    changed = df.dropna(subset=['Change (%)'])
+    return {
+        'Top Gainers': changed[changed['Change (%)'] > 0].sort_values('Change (%)', ascending=False).head(count),
+        'Top Losers': changed[changed['Change (%)'] < 0].sort_values('Change (%)').head(count),
+        'Most Active': df.sort_values('Volume', ascending=False).head(count),
+    }
@@ -0,0 +1,51 @@
+from dataclasses import dataclass
+from typing import Optional
+
+# Markets the viewer supports and the settings that differ between them
+# Forex, crypto and index tickers carry a prefix ('C:EURUSD', 'X:BTCUSD', 'I:SPX'), stock tickers have none
+
+@dataclass(frozen=True)
+class Market:
+    name: str  # Market name used by the ticker reference data ('stocks', 'fx', 'crypto' or 'indices')
+    label: str
+    ticker_prefix: str
+    snapshot_path: Optional[str]  # Snapshot endpoints below /v2/snapshot (index snapshots come from /v3/snapshot/indices)
+    grouped_path: Optional[str]  # Grouped daily endpoint below /v2/aggs/grouped (there is none for indices)
+    split_adjusted: bool  # Only stock prices are adjusted for splits
+    round_the_clock: bool  # Forex and crypto have no regular session (forex trades 24/5, crypto 24/7)
+    watchlist: tuple
+    benchmark: str
+    stream_cluster: str  # WebSocket cluster below the stream URL, e.g. wss://socket.polygon.io/stocks
+
+
+MARKETS = {
+    'stocks': Market('stocks', 'Stocks', '', 'locale/us/markets/stocks', 'locale/us/market/stocks', True, False,
+                     ('AAPL', 'MSFT', 'NVDA', 'TSLA', 'SPY'), 'SPY', 'stocks'),
+    'fx': Market('fx', 'Forex', 'C:', 'locale/global/markets/forex', 'locale/global/market/fx', False, True,
+                 ('C:EURUSD', 'C:GBPUSD', 'C:USDJPY'), 'C:EURUSD', 'forex'),
+    'crypto': Market('crypto', 'Crypto', 'X:', 'locale/global/markets/crypto', 'locale/global/market/crypto', False, True,
+                     ('X:BTCUSD', 'X:ETHUSD'), 'X:BTCUSD', 'crypto'),
+    'indices': Market('indices', 'Indices', 'I:', None, None, False, False,
+                      ('I:SPX', 'I:NDX', 'I:DJI', 'I:VIX'), 'I:SPX', 'indices'),
+}
+
+# Volatility indices quote implied volatility rather than prices, so they are charted apart from index returns
+VOLATILITY_INDICES = {'I:VIX', 'I:VXN', 'I:RVX'}
+
+# Look up a market by name
+def get_market(name):
+    if name not in MARKETS:
+        raise ValueError(f"Unknown market '{name}', expected one of {list(MARKETS)}")
+    return MARKETS[name]
+
+# Market of a ticker, from its prefix
+def market_of(ticker):
+    for market in MARKETS.values():
+        if market.ticker_prefix and ticker.startswith(market.ticker_prefix):
+            return market
+    return MARKETS['stocks']
+
+# Base and quote currencies of a forex or crypto pair, e.g. ('EUR', 'USD') for 'C:EURUSD'
+def currency_pair(ticker):
+    symbol = ticker.split(':', 1)[-1]
+    return symbol[:-3], symbol[-3:]
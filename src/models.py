@@ -0,0 +1,533 @@
+from dataclasses import dataclass, field, fields
+from typing import Dict, List, Optional
+
+
+# Build a dataclass from an API payload, ignoring keys the model does not define
+def from_dict(cls, data):
+    names = {f.name for f in fields(cls)}
+    return cls(**{key: value for key, value in data.items() if key in names})
+
+
+# Aggregate bar from /v2/aggs (timestamp is the bar start in Unix milliseconds)
+# ticker is only set by endpoints covering several tickers or a single bar (grouped daily, previous close)
+# Index bars have no volume, VWAP or transactions
+@dataclass
+class Aggregate:
+    timestamp: int
+    open: float
+    high: float
+    low: float
+    close: float
+    volume: Optional[float] = None
+    vwap: Optional[float] = None
+    transactions: Optional[int] = None
+    ticker: Optional[str] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return cls(
+            timestamp=data['t'],
+            open=data['o'],
+            high=data['h'],
+            low=data['l'],
+            close=data['c'],
+            volume=data.get('v'),
+            vwap=data.get('vw'),
+            transactions=data.get('n'),
+            ticker=data.get('T'),
+        )
+
+    # Second or minute aggregate event of the WebSocket feed (A, AM, XA, CA...; s is the bar start in Unix milliseconds)
+    @classmethod
+    def from_stream(cls, data, ticker):
+        return cls(
+            timestamp=data['s'],
+            open=data['o'],
+            high=data['h'],
+            low=data['l'],
+            close=data['c'],
+            volume=data.get('v'),
+            vwap=data.get('vw'),
+            ticker=ticker,
+        )
+
+
+# Value of a server-side indicator from /v1/indicators (timestamp is the bar start in Unix milliseconds)
+# signal and histogram are only set for MACD, whose value is the MACD line
+@dataclass
+class IndicatorValue:
+    timestamp: int
+    value: float
+    signal: Optional[float] = None
+    histogram: Optional[float] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Daily open, close and extended-hours prices from /v1/open-close/{ticker}/{date}
+@dataclass
+class DailyOpenClose:
+    symbol: str
+    date: str
+    open: float
+    high: float
+    low: float
+    close: float
+    volume: float
+    pre_market: Optional[float] = None
+    after_hours: Optional[float] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return cls(
+            symbol=data['symbol'],
+            date=data['from'],
+            open=data['open'],
+            high=data['high'],
+            low=data['low'],
+            close=data['close'],
+            volume=data['volume'],
+            pre_market=data.get('preMarket'),
+            after_hours=data.get('afterHours'),
+        )
+
+
+# Current trading state of a ticker from /v2/snapshot (updated is in Unix nanoseconds)
+@dataclass
+class TickerSnapshot:
+    ticker: str
+    todays_change: Optional[float] = None
+    todays_change_percent: Optional[float] = None
+    updated: Optional[int] = None
+    last_trade_price: Optional[float] = None
+    last_trade_size: Optional[float] = None
+    bid: Optional[float] = None
+    ask: Optional[float] = None
+    day_open: Optional[float] = None
+    day_high: Optional[float] = None
+    day_low: Optional[float] = None
+    day_close: Optional[float] = None
+    day_volume: Optional[float] = None
+    day_vwap: Optional[float] = None
+    prev_close: Optional[float] = None
+
+    @classmethod
+    def from_api(cls, data):
+        day = data.get('day') or {}
+        last_trade = data.get('lastTrade') or {}
+        last_quote = data.get('lastQuote') or {}
+        return cls(
+            ticker=data['ticker'],
+            todays_change=data.get('todaysChange'),
+            todays_change_percent=data.get('todaysChangePerc'),
+            updated=data.get('updated'),
+            last_trade_price=last_trade.get('p'),
+            last_trade_size=last_trade.get('s'),
+            # Stock quotes use p/P, forex quotes b/a (crypto snapshots have no quote)
+            bid=last_quote.get('p', last_quote.get('b')),
+            ask=last_quote.get('P', last_quote.get('a')),
+            day_open=day.get('o'),
+            day_high=day.get('h'),
+            day_low=day.get('l'),
+            day_close=day.get('c'),
+            day_volume=day.get('v'),
+            day_vwap=day.get('vw'),
+            prev_close=(data.get('prevDay') or {}).get('c'),
+        )
+
+    # Index snapshots from /v3/snapshot/indices report the index value and the session's prices instead of trades and quotes
+    @classmethod
+    def from_index_api(cls, data):
+        session = data.get('session') or {}
+        return cls(
+            ticker=data['ticker'],
+            todays_change=session.get('change'),
+            todays_change_percent=session.get('change_percent'),
+            updated=data.get('last_updated'),
+            last_trade_price=data.get('value'),
+            day_open=session.get('open'),
+            day_high=session.get('high'),
+            day_low=session.get('low'),
+            day_close=session.get('close'),
+            prev_close=session.get('previous_close'),
+        )
+
+
+# Currency conversion from /v1/conversion/{from}/{to} at the last forex quote (timestamp is Unix milliseconds)
+@dataclass
+class CurrencyConversion:
+    from_currency: str
+    to_currency: str
+    initial_amount: float
+    converted: float
+    bid: Optional[float] = None
+    ask: Optional[float] = None
+    timestamp: Optional[int] = None
+
+    @classmethod
+    def from_api(cls, data):
+        last = data.get('last') or {}
+        return cls(
+            from_currency=data['from'],
+            to_currency=data['to'],
+            initial_amount=data.get('initialAmount'),
+            converted=data.get('converted'),
+            bid=last.get('bid'),
+            ask=last.get('ask'),
+            timestamp=last.get('timestamp'),
+        )
+
+
+# Trade from /v3/trades/{ticker} (timestamps are Unix nanoseconds)
+@dataclass
+class Trade:
+    sip_timestamp: int
+    price: float
+    size: float
+    exchange: Optional[int] = None
+    conditions: List[int] = field(default_factory=list)
+    id: Optional[str] = None
+    sequence_number: Optional[int] = None
+    participant_timestamp: Optional[int] = None
+    tape: Optional[int] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# NBBO quote from /v3/quotes/{ticker} (timestamps are Unix nanoseconds)
+@dataclass
+class Quote:
+    sip_timestamp: int
+    bid_price: Optional[float] = None
+    bid_size: Optional[float] = None
+    bid_exchange: Optional[int] = None
+    ask_price: Optional[float] = None
+    ask_size: Optional[float] = None
+    ask_exchange: Optional[int] = None
+    conditions: List[int] = field(default_factory=list)
+    sequence_number: Optional[int] = None
+    participant_timestamp: Optional[int] = None
+    tape: Optional[int] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Trade event of the WebSocket feed (T for stocks, XT for crypto; timestamp is Unix milliseconds)
+@dataclass
+class StreamTrade:
+    ticker: str
+    timestamp: int
+    price: float
+    size: float
+    exchange: Optional[int] = None
+    conditions: List[int] = field(default_factory=list)
+
+    @classmethod
+    def from_stream(cls, data, ticker):
+        return cls(ticker=ticker, timestamp=data['t'], price=data['p'], size=data.get('s') or 0, exchange=data.get('x'), conditions=data.get('c') or [])
+
+
+# Quote event of the WebSocket feed (Q for stocks, XQ for crypto, C for forex, which has no sizes; timestamp is Unix milliseconds)
+@dataclass
+class StreamQuote:
+    ticker: str
+    timestamp: int
+    bid_price: Optional[float] = None
+    bid_size: Optional[float] = None
+    ask_price: Optional[float] = None
+    ask_size: Optional[float] = None
+
+    @classmethod
+    def from_stream(cls, data, ticker):
+        return cls(ticker=ticker, timestamp=data['t'], bid_price=data.get('bp', data.get('b')), bid_size=data.get('bs'),
+                   ask_price=data.get('ap', data.get('a')), ask_size=data.get('as'))
+
+
+# Exchange from /v3/reference/exchanges
+@dataclass
+class Exchange:
+    id: int
+    name: str
+    type: Optional[str] = None
+    asset_class: Optional[str] = None
+    locale: Optional[str] = None
+    acronym: Optional[str] = None
+    mic: Optional[str] = None
+    operating_mic: Optional[str] = None
+    participant_id: Optional[str] = None
+    url: Optional[str] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Trade or quote condition from /v3/reference/conditions
+@dataclass
+class Condition:
+    id: int
+    name: str
+    type: Optional[str] = None
+    asset_class: Optional[str] = None
+    data_types: List[str] = field(default_factory=list)
+    abbreviation: Optional[str] = None
+    description: Optional[str] = None
+    sip_mapping: Optional[Dict[str, str]] = None
+    legacy: Optional[bool] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Current market status from /v1/marketstatus/now; market is 'open', 'closed' or 'extended-hours'
+@dataclass
+class MarketStatus:
+    market: str
+    server_time: Optional[str] = None
+    early_hours: Optional[bool] = None
+    after_hours: Optional[bool] = None
+    exchanges: Dict[str, str] = field(default_factory=dict)
+    currencies: Dict[str, str] = field(default_factory=dict)
+    indices_groups: Dict[str, str] = field(default_factory=dict)
+
+    @classmethod
+    def from_api(cls, data):
+        return cls(
+            market=data['market'],
+            server_time=data.get('serverTime'),
+            early_hours=data.get('earlyHours'),
+            after_hours=data.get('afterHours'),
+            exchanges=data.get('exchanges') or {},
+            currencies=data.get('currencies') or {},
+            indices_groups=data.get('indicesGroups') or {},
+        )
+
+
+# Upcoming holiday of one exchange from /v1/marketstatus/upcoming; status is 'closed' or 'early-close' (with open and close times)
+@dataclass
+class MarketHoliday:
+    exchange: str
+    name: str
+    date: str
+    status: str
+    open: Optional[str] = None
+    close: Optional[str] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Options contract from /v3/reference/options/contracts; ticker is the OCC symbol, e.g. 'O:AAPL240119C00185000'
+@dataclass
+class OptionsContract:
+    ticker: str
+    underlying_ticker: str
+    contract_type: str
+    expiration_date: str
+    strike_price: float
+    exercise_style: Optional[str] = None
+    shares_per_contract: Optional[int] = None
+    primary_exchange: Optional[str] = None
+    cfi: Optional[str] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Contract of an options chain snapshot from /v3/snapshot/options/{underlying}, flattened
+# Greeks and implied volatility are missing when Polygon cannot compute them (e.g. deep in or out of the money)
+@dataclass
+class OptionSnapshot:
+    ticker: str
+    contract_type: str
+    expiration_date: str
+    strike_price: float
+    shares_per_contract: Optional[int] = None
+    break_even_price: Optional[float] = None
+    implied_volatility: Optional[float] = None
+    open_interest: Optional[int] = None
+    delta: Optional[float] = None
+    gamma: Optional[float] = None
+    theta: Optional[float] = None
+    vega: Optional[float] = None
+    bid: Optional[float] = None
+    ask: Optional[float] = None
+    midpoint: Optional[float] = None
+    last_price: Optional[float] = None
+    day_volume: Optional[float] = None
+    day_change_percent: Optional[float] = None
+    underlying_price: Optional[float] = None
+    underlying_updated: Optional[int] = None
+
+    @classmethod
+    def from_api(cls, data):
+        details = data.get('details') or {}
+        greeks = data.get('greeks') or {}
+        day = data.get('day') or {}
+        last_quote = data.get('last_quote') or {}
+        underlying = data.get('underlying_asset') or {}
+        return cls(
+            ticker=details.get('ticker'),
+            contract_type=details.get('contract_type'),
+            expiration_date=details.get('expiration_date'),
+            strike_price=details.get('strike_price'),
+            shares_per_contract=details.get('shares_per_contract'),
+            break_even_price=data.get('break_even_price'),
+            implied_volatility=data.get('implied_volatility'),
+            open_interest=data.get('open_interest'),
+            delta=greeks.get('delta'),
+            gamma=greeks.get('gamma'),
+            theta=greeks.get('theta'),
+            vega=greeks.get('vega'),
+            bid=last_quote.get('bid'),
+            ask=last_quote.get('ask'),
+            midpoint=last_quote.get('midpoint'),
+            last_price=(data.get('last_trade') or {}).get('price', day.get('close')),
+            day_volume=day.get('volume'),
+            day_change_percent=day.get('change_percent'),
+            underlying_price=underlying.get('price'),
+            underlying_updated=underlying.get('last_updated'),
+        )
+
+
+# Ticker details from /v3/reference/tickers/{ticker}
+@dataclass
+class TickerDetails:
+    ticker: str
+    name: Optional[str] = None
+    market: Optional[str] = None
+    locale: Optional[str] = None
+    primary_exchange: Optional[str] = None
+    type: Optional[str] = None
+    active: Optional[bool] = None
+    currency_name: Optional[str] = None
+    cik: Optional[str] = None
+    composite_figi: Optional[str] = None
+    share_class_figi: Optional[str] = None
+    market_cap: Optional[float] = None
+    phone_number: Optional[str] = None
+    address: Optional[Dict[str, str]] = None
+    description: Optional[str] = None
+    sic_code: Optional[str] = None
+    sic_description: Optional[str] = None
+    ticker_root: Optional[str] = None
+    homepage_url: Optional[str] = None
+    total_employees: Optional[int] = None
+    list_date: Optional[str] = None
+    branding: Optional[Dict[str, str]] = None
+    share_class_shares_outstanding: Optional[int] = None
+    weighted_shares_outstanding: Optional[int] = None
+    round_lot: Optional[int] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Single line item of a financial statement (e.g. revenues in the income statement)
+@dataclass
+class FinancialDataPoint:
+    label: Optional[str] = None
+    value: Optional[float] = None
+    unit: Optional[str] = None
+    order: Optional[int] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Financial report from /vX/reference/financials; financials maps statement -> line item key -> data point
+@dataclass
+class FinancialReport:
+    cik: Optional[str] = None
+    company_name: Optional[str] = None
+    fiscal_year: Optional[str] = None
+    fiscal_period: Optional[str] = None
+    start_date: Optional[str] = None
+    end_date: Optional[str] = None
+    filing_date: Optional[str] = None
+    timeframe: Optional[str] = None
+    financials: Dict[str, Dict[str, FinancialDataPoint]] = field(default_factory=dict)
+
+    @classmethod
+    def from_api(cls, data):
+        report = from_dict(cls, data)
+        report.financials = {
+            section: {key: FinancialDataPoint.from_api(value) for key, value in section_data.items()}
+            for section, section_data in data.get('financials', {}).items()
+        }
+        return report
+
+    # Line items of a statement ('income_statement', 'balance_sheet'...) as (key, data point) in filing order
+    def statement(self, name):
+        items = self.financials.get(name, {})
+        return sorted(items.items(), key=lambda item: item[1].order if item[1].order is not None else float('inf'))
+
+    # Heading of the report's period: 'Q1 2024', 'FY 2023' or 'TTM 2023-12-30' (trailing reports are named by their end)
+    @property
+    def period_label(self):
+        if self.timeframe == 'ttm':
+            return f"TTM {self.end_date}"
+        return f"{self.fiscal_period} {self.fiscal_year}"
+
+
+# Stock split from /v3/reference/splits
+@dataclass
+class StockSplit:
+    ticker: str
+    execution_date: str
+    split_from: float
+    split_to: float
+    id: Optional[str] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# Dividend from /v3/reference/dividends
+@dataclass
+class Dividend:
+    ticker: str
+    cash_amount: float
+    declaration_date: Optional[str] = None
+    ex_dividend_date: Optional[str] = None
+    record_date: Optional[str] = None
+    pay_date: Optional[str] = None
+    frequency: Optional[int] = None
+    dividend_type: Optional[str] = None
+    currency: Optional[str] = None
+    id: Optional[str] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
+
+
+# News article from /v2/reference/news
+@dataclass
+class NewsArticle:
+    id: Optional[str] = None
+    title: Optional[str] = None
+    author: Optional[str] = None
+    published_utc: Optional[str] = None
+    article_url: Optional[str] = None
+    image_url: Optional[str] = None
+    description: Optional[str] = None
+    tickers: List[str] = field(default_factory=list)
+    keywords: List[str] = field(default_factory=list)
+    publisher: Optional[Dict[str, str]] = None
+
+    @classmethod
+    def from_api(cls, data):
+        return from_dict(cls, data)
@@ -0,0 +1,108 @@
+import math
+import pandas as pd
+
+# Black-Scholes pricing for European options, used to fill in values and greeks the API does not provide
+# Times are in years, rates and volatilities are annualized decimals (0.05 for 5%)
+
+DAYS_PER_YEAR = 365.0
+
+# Standard normal cumulative distribution and density
+def norm_cdf(x):
+    return 0.5 * (1.0 + math.erf(x / math.sqrt(2.0)))
+
+def norm_pdf(x):
+    return math.exp(-0.5 * x * x) / math.sqrt(2.0 * math.pi)
+
+def _d1_d2(spot, strike, years, rate, volatility, dividend_yield):
+    d1 = (math.log(spot / strike) + (rate - dividend_yield + 0.5 * volatility ** 2) * years) / (volatility * math.sqrt(years))
+    return d1, d1 - volatility * math.sqrt(years)
+
+# Theoretical price of a call or put ('call' or 'put')
+def black_scholes_price(spot, strike, years, rate, volatility, contract_type='call', dividend_yield=0.0):
+    if years <= 0 or volatility <= 0:
+        # At expiry (or without volatility) the option is worth its discounted intrinsic value
+        forward = spot * math.exp((rate - dividend_yield) * max(years, 0))
+        discount = math.exp(-rate * max(years, 0))
+        intrinsic = forward - strike if contract_type == 'call' else strike - forward
+        return discount * max(intrinsic, 0.0)
+    d1, d2 = _d1_d2(spot, strike, years, rate, volatility, dividend_yield)
+    spot_discount, strike_discount = math.exp(-dividend_yield * years), math.exp(-rate * years)
+    if contract_type == 'call':
+        return spot * spot_discount * norm_cdf(d1) - strike * strike_discount * norm_cdf(d2)
+    return strike * strike_discount * norm_cdf(-d2) - spot * spot_discount * norm_cdf(-d1)
+
+# Greeks of a call or put: theta per calendar day, vega and rho per percentage point
+def black_scholes_greeks(spot, strike, years, rate, volatility, contract_type='call', dividend_yield=0.0):
+    if years <= 0 or volatility <= 0:
+        return {'delta': float('nan'), 'gamma': float('nan'), 'theta': float('nan'), 'vega': float('nan'), 'rho': float('nan')}
+    d1, d2 = _d1_d2(spot, strike, years, rate, volatility, dividend_yield)
+    spot_discount, strike_discount = math.exp(-dividend_yield * years), math.exp(-rate * years)
+    gamma = spot_discount * norm_pdf(d1) / (spot * volatility * math.sqrt(years))
+    vega = spot * spot_discount * norm_pdf(d1) * math.sqrt(years)
+    decay = -spot * spot_discount * norm_pdf(d1) * volatility / (2 * math.sqrt(years))
+    if contract_type == 'call':
+        delta = spot_discount * norm_cdf(d1)
+        theta = decay - rate * strike * strike_discount * norm_cdf(d2) + dividend_yield * spot * spot_discount * norm_cdf(d1)
+        rho = strike * years * strike_discount * norm_cdf(d2)
+    else:
+        delta = -spot_discount * norm_cdf(-d1)
+        theta = decay + rate * strike * strike_discount * norm_cdf(-d2) - dividend_yield * spot * spot_discount * norm_cdf(-d1)
+        rho = -strike * years * strike_discount * norm_cdf(-d2)
+    return {'delta': delta, 'gamma': gamma, 'theta': theta / DAYS_PER_YEAR, 'vega': vega / 100, 'rho': rho / 100}
+
+# Volatility at which the Black-Scholes price matches a market price (None when no volatility does), by bisection
+def implied_volatility(price, spot, strike, years, rate, contract_type='call', dividend_yield=0.0, low=1e-4, high=5.0, tolerance=1e-6):
+    if years <= 0 or price <= 0:
+        return None
+    price_at = lambda volatility: black_scholes_price(spot, strike, years, rate, volatility, contract_type, dividend_yield)
+    if not price_at(low) <= price <= price_at(high):
+        return None
+    for _ in range(200):
+        middle = (low + high) / 2
+        if price_at(middle) < price:
+            low = middle
+        else:
+            high = middle
+        if high - low < tolerance:
+            break
+    return (low + high) / 2
+
+# Years from a moment to an expiration date, counting to the 16:00 US/Eastern close
+def years_to_expiry(expiration_date, as_of):
+    expiry = pd.Timestamp(f"{expiration_date} 16:00").tz_localize('US/Eastern')
+    return max((expiry - pd.Timestamp(as_of)).total_seconds(), 0) / (DAYS_PER_YEAR * 24 * 60 * 60)
+
+# Fill in implied volatility (from the mid price) and greeks (from the implied volatility) where the chain lacks them,
+# and add the Black-Scholes value of each contract; 'Computed' marks the rows filled in locally
+def fill_missing_greeks(chain, spot, rate, as_of, dividend_yield=0.0):
+    chain = chain.copy()
+    chain['Computed'] = False
+    theoretical = []
+    for index, row in chain.iterrows():
+        years = years_to_expiry(row['Expiration'], as_of)
+        volatility = row['IV']
+        if pd.isna(volatility) and pd.notna(row['Mid']):
+            volatility = implied_volatility(row['Mid'], spot, row['Strike'], years, rate, row['Type'], dividend_yield)
+            if volatility is not None:
+                chain.at[index, 'IV'] = volatility
+                chain.at[index, 'Computed'] = True
+        if volatility is None or pd.isna(volatility):
+            theoretical.append(float('nan'))
+            continue
+        if chain.loc[index, ['Delta', 'Gamma', 'Theta', 'Vega']].isna().any():
+            greeks = black_scholes_greeks(spot, row['Strike'], years, rate, volatility, row['Type'], dividend_yield)
+            for greek in ('Delta', 'Gamma', 'Theta', 'Vega'):
+                if pd.isna(chain.at[index, greek]):
+                    chain.at[index, greek] = greeks[greek.lower()]
+            chain.at[index, 'Computed'] = True
+        theoretical.append(black_scholes_price(spot, row['Strike'], years, rate, volatility, row['Type'], dividend_yield))
+    chain['Theoretical'] = theoretical
+    return chain
+
+# Lay out a chain with calls on the left and puts on the right of each strike
+def chain_by_strike(chain, columns=('Last', 'Bid', 'Ask', 'IV', 'Delta', 'Open Interest', 'Volume')):
+    sides = []
+    for contract_type, prefix in (('call', 'Call'), ('put', 'Put')):
+        side = chain[chain['Type'] == contract_type].set_index('Strike')[list(columns)]
+        sides.append(side.add_prefix(f"{prefix} "))
+    return pd.concat(sides, axis=1).sort_index()
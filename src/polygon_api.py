@@ -1,59 +1,479 @@
+import math
+import threading
+import time
 import streamlit as st
-import requests
 import pandas as pd
+from dataclasses import asdict
+from datetime import date, timedelta
 import config.log_config
-
-# Read the API_KEY from secrets
-API_KEY = st.secrets['API_KEY']
+import config.cache_config as cache_config
+from cache import CacheMissError, ResponseCache
+from polygon_client import PolygonClient, PolygonAPIError
+from comparison import align_close_prices
+from resample import RESAMPLE_INTERVALS, filter_session, resample_bars
+from financial_metrics import financial_metrics, metrics_history
+from indicators import SERVER_INDICATORS, warmup_bars, local_indicator, compare_indicator
+from market_day import add_daily_change
+from tick_codes import EXCHANGES, TRADE_CONDITIONS, exchange_name, condition_names
+from market_calendar import previous_trading_day
+from markets import market_of
 
 # Initialize the logger
 logger = config.log_config.setup_logging()
 
-# Apply comma formatting to the entire DataFrame
-def format_with_comma(df):
-    for col in df.select_dtypes(include=['float', 'int']).columns:
-        df[col] = df[col].apply(lambda x: f"{x:,.2f}")
-    return df
+# Get a Polygon client for the API key, shared across reruns and sessions
+@st.cache_resource
+def get_client(api_key):
+    return PolygonClient(api_key, cache=ResponseCache(), offline=cache_config.OFFLINE_MODE)
+
+# Stock and forex volumes are whole shares and ticks, crypto volumes are fractional coins
+def round_volume(volume, market):
+    return volume.astype('float64') if market == 'crypto' else volume.round().astype('int64')
+
+# Bar start times in US/Eastern from Unix milliseconds
+def bar_times(timestamps, ticker, timespan):
+    if market_of(ticker).round_the_clock and timespan not in ('second', 'minute', 'hour'):
+        # Forex and crypto days start at midnight UTC, so their daily and longer bars stay in UTC to keep their date
+        return pd.to_datetime(timestamps, unit='ms', utc=True)
+    return pd.to_datetime(timestamps, unit='ms', utc=True).dt.tz_convert('US/Eastern')
 
-# Get historical stock data from Polygon API
+
+# Get historical stock, forex or crypto data from Polygon API, indexed by bar start time in US/Eastern (UTC for forex and crypto days)
 @st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
-def get_historical_data_as_df(ticker, from_date, to_date, adjusted, timespan, api_key):
-    adjusted_param = 'true' if adjusted else 'false'
-    url = f"https://api.polygon.io/v2/aggs/ticker/{ticker}/range/1/{timespan}/{from_date}/{to_date}?adjusted={adjusted_param}&apiKey={api_key}"
-    logger.info(f"Requesting historical data for {ticker} from {from_date} to {to_date} with adjusted={adjusted_param} and timespan={timespan}") # Log the request
-    response = requests.get(url)
-    if response.status_code == 200:
-        data = response.json().get('results', [])
-        if data:
-            df = pd.DataFrame(data)
-            df['t'] = pd.to_datetime(df['t'], unit='ms').dt.date
-            df.rename(columns={'t': 'Date', 'o': 'Open', 'h': 'High', 'l': 'Low', 'c': 'Close', 'v': 'Volume'}, inplace=True)
-            df = df[['Date', 'Open', 'High', 'Low', 'Close', 'Volume']]
-            df = format_with_comma(df)  # Apply comma formatting
-            return df
-        else:
-            logger.warning(f"No data found for {ticker} from {from_date} to {to_date}")
-            return pd.DataFrame()  # Return empty dataframe if no data found
+def get_historical_data_as_df(ticker, from_date, to_date, adjusted, timespan, api_key, multiplier=1, sort='asc', limit=None):
+    try:
+        bars = get_client(api_key).get_aggregates(ticker, from_date, to_date, timespan=timespan, multiplier=multiplier, adjusted=adjusted, sort=sort, limit=limit)
+    except Exception:
+        logger.error(f"API request failed for {ticker} from {from_date} to {to_date}")
+        raise
+    if bars:
+        df = pd.DataFrame([asdict(bar) for bar in bars])
+        df['timestamp'] = bar_times(df['timestamp'], ticker, timespan)
+        df.rename(columns={'timestamp': 'Date', 'open': 'Open', 'high': 'High', 'low': 'Low', 'close': 'Close', 'volume': 'Volume',
+                           'vwap': 'VWAP', 'transactions': 'Transactions'}, inplace=True)
+        df = df[['Date', 'Open', 'High', 'Low', 'Close', 'Volume', 'VWAP', 'Transactions']].set_index('Date')
+        if market_of(ticker).name == 'indices':
+            # Indices are not traded, their bars only have prices
+            return df[['Open', 'High', 'Low', 'Close']].astype('float64')
+        # Keep prices as floats and volume as integers so the data can be sorted, plotted and computed on
+        df = df.astype({'Open': 'float64', 'High': 'float64', 'Low': 'float64', 'Close': 'float64', 'VWAP': 'float64'})
+        df['Volume'] = round_volume(df['Volume'], market_of(ticker).name)
+        df['Transactions'] = df['Transactions'].astype('Int64')  # Not reported for every bar
+        return df
+    else:
+        logger.warning(f"No data found for {ticker} from {from_date} to {to_date}")
+        return pd.DataFrame()  # Return empty dataframe if no data found
+
+
+# Calendar days spanned by one bar of each timespan, counting 5 trading days a week and 6.5 trading hours a day
+CALENDAR_DAYS_PER_BAR = {'minute': 7 / 5 / 390, 'hour': 7 / 5 / 6.5, 'day': 7 / 5, 'week': 7, 'month': 31, 'quarter': 92, 'year': 366}
+
+
+# Get one of Polygon's server-side indicators ('SMA', 'EMA', 'RSI' or 'MACD') with the parameters of the local indicator,
+# indexed like get_historical_data_as_df and with the columns of local_indicator
+@st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
+def get_server_indicator_as_df(ticker, name, from_date, to_date, adjusted, timespan, api_key, series_type='close', **params):
+    windows = {SERVER_INDICATORS[name][param]: value for param, value in params.items()}
+    try:
+        values = getattr(get_client(api_key), f"get_{name.lower()}")(ticker, from_date, to_date, timespan=timespan, adjusted=adjusted, series_type=series_type, **windows)
+    except Exception:
+        logger.error(f"Failed to retrieve {name} for {ticker} from {from_date} to {to_date}")
+        raise
+    columns = {'value': 'MACD', 'signal': 'Signal', 'histogram': 'Histogram'} if name == 'MACD' else {'value': name}
+    df = pd.DataFrame([asdict(value) for value in values], columns=['timestamp', 'value', 'signal', 'histogram'])
+    df['Date'] = bar_times(df['timestamp'], ticker, timespan)
+    return df.set_index('Date')[list(columns)].rename(columns=columns).astype('float64')
+
+
+# Cross-check a server-side indicator against the same indicator computed locally from get_historical_data_as_df
+# Local bars start early enough for the moving averages to fill and the smoothing to settle before from_date
+def validate_indicator(ticker, name, from_date, to_date, adjusted, timespan, api_key, series_type='close', tolerance=1e-3, **params):
+    server = get_server_indicator_as_df(ticker, name, from_date, to_date, adjusted, timespan, api_key, series_type=series_type, **params)
+    warmup_days = math.ceil(warmup_bars(name, **params) * CALENDAR_DAYS_PER_BAR[timespan]) + 7  # A week more for holidays
+    warmup_from = (date.fromisoformat(str(from_date)) - timedelta(days=warmup_days)).isoformat()
+    bars = get_historical_data_as_df(ticker, warmup_from, to_date, adjusted, timespan, api_key)
+    if bars.empty:
+        local = pd.DataFrame(index=server.index, columns=server.columns, dtype='float64')
     else:
-        logger.error(f"API request failed for {ticker} with status code {response.status_code}: {response.text}")
-        raise Exception(f"API request failed with status code {response.status_code}: {response.text}")
+        local = local_indicator(bars, name, series_type, **params)
+    report = compare_indicator(server, local, tolerance)
+    mismatches = (~report['Match']).sum()
+    if mismatches:
+        logger.warning(f"{name} of {ticker}: {mismatches} of {len(report)} server values differ from the local computation")
+    return report
+
+
+# Get bars of any interval in RESAMPLE_INTERVALS by resampling minute bars locally, so every interval shares one cached minute fetch
+def get_resampled_data_as_df(ticker, from_date, to_date, adjusted, interval, api_key, session='regular'):
+    df = get_historical_data_as_df(ticker, from_date, to_date, adjusted, 'minute', api_key)
+    if df.empty:
+        return df
+    day_timezone = None
+    if market_of(ticker).round_the_clock:
+        session = 'extended'  # Keep every bar of markets without a regular session
+        day_timezone = 'UTC'  # whose days start at midnight UTC
+    return resample_bars(filter_session(df, session), RESAMPLE_INTERVALS[interval], day_timezone)
+
+
+# Get close prices of several tickers aligned on their common dates
+def get_close_prices_as_df(tickers, from_date, to_date, adjusted, timespan, api_key, multiplier=1):
+    frames = {}
+    for ticker in tickers:
+        df = get_historical_data_as_df(ticker, from_date, to_date, adjusted, timespan, api_key, multiplier=multiplier)
+        if df.empty:
+            logger.warning(f"No data found for {ticker} from {from_date} to {to_date}, leaving it out of the comparison")
+            continue
+        frames[ticker] = df
+    return align_close_prices(frames)
+
+
+# Get the previous trading day's bar for a ticker (None when there is none)
+@st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
+def get_previous_close(ticker, adjusted, api_key):
+    try:
+        return get_client(api_key).get_previous_close(ticker, adjusted)
+    except Exception:
+        logger.error(f"Failed to retrieve previous close for {ticker}")
+        raise
+
+
+# Get the open, close, pre-market and after-hours prices of a ticker on one day (None when the market was closed)
+@st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
+def get_daily_open_close(ticker, day, adjusted, api_key):
+    try:
+        return get_client(api_key).get_daily_open_close(ticker, day, adjusted)
+    except PolygonAPIError as e:
+        if e.status_code == 404:
+            logger.warning(f"No open/close data for {ticker} on {day}")
+            return None
+        logger.error(f"Failed to retrieve open/close data for {ticker} on {day}")
+        raise
+
+
+# Get the daily bars of every ticker of a market ('stocks', 'fx' or 'crypto') on one day, indexed by ticker
+@st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
+def get_grouped_daily_as_df(day, adjusted, api_key, market='stocks'):
+    try:
+        bars = get_client(api_key).get_grouped_daily(day, adjusted, market=market)
+    except Exception:
+        logger.error(f"Failed to retrieve grouped daily {market} bars for {day}")
+        raise
+    if not bars:
+        logger.warning(f"No grouped daily bars found for {day}")
+        return pd.DataFrame()
+    df = pd.DataFrame([asdict(bar) for bar in bars])
+    df.rename(columns={'ticker': 'Ticker', 'open': 'Open', 'high': 'High', 'low': 'Low', 'close': 'Close', 'volume': 'Volume',
+                       'vwap': 'VWAP', 'transactions': 'Transactions'}, inplace=True)
+    df = df[['Ticker', 'Open', 'High', 'Low', 'Close', 'Volume', 'VWAP', 'Transactions']].set_index('Ticker')
+    df = df.astype({'Open': 'float64', 'High': 'float64', 'Low': 'float64', 'Close': 'float64', 'VWAP': 'float64'})
+    df['Volume'] = round_volume(df['Volume'], market)
+    df['Transactions'] = df['Transactions'].astype('Int64')
+    return df
+
+
+# Get the whole market's bars on one day with the change from the previous trading day's close
+def get_market_day_as_df(day, adjusted, api_key, market='stocks'):
+    bars = get_grouped_daily_as_df(day, adjusted, api_key, market)
+    if bars.empty:
+        return bars
+    previous_bars = get_grouped_daily_as_df(previous_trading_day(day, market=market).isoformat(), adjusted, api_key, market)
+    return add_daily_change(bars, previous_bars)
+
+
+# Columns of the snapshot table, from TickerSnapshot fields
+SNAPSHOT_COLUMNS = {
+    'ticker': 'Ticker', 'last_trade_price': 'Last Trade', 'todays_change': 'Change', 'todays_change_percent': 'Change (%)',
+    'day_volume': 'Volume', 'day_vwap': 'VWAP', 'day_open': 'Open', 'day_high': 'High', 'day_low': 'Low',
+    'prev_close': 'Previous Close', 'bid': 'Bid', 'ask': 'Ask', 'updated': 'Updated',
+}
+
+
+# Create a table of ticker snapshots indexed by ticker
+def create_snapshots_dataframe(snapshots):
+    if not snapshots:
+        return pd.DataFrame(columns=list(SNAPSHOT_COLUMNS.values())[1:]).rename_axis('Ticker')
+    df = pd.DataFrame([asdict(snapshot) for snapshot in snapshots])[list(SNAPSHOT_COLUMNS)].rename(columns=SNAPSHOT_COLUMNS)
+    df['Updated'] = pd.to_datetime(df['Updated'], unit='ns', utc=True).dt.tz_convert('US/Eastern')
+    return df.set_index('Ticker')
+
+
+# Get live snapshots of a watchlist of one market (not cached by Streamlit so every refresh sees new data)
+def get_snapshots_as_df(tickers, api_key, market='stocks'):
+    try:
+        snapshots = get_client(api_key).get_snapshots(tickers, market=market)
+    except Exception:
+        logger.error(f"Failed to retrieve snapshots for {', '.join(tickers)}")
+        raise
+    missing = set(tickers) - {snapshot.ticker for snapshot in snapshots}
+    if missing:
+        logger.warning(f"No snapshot found for {', '.join(sorted(missing))}")
+    df = create_snapshots_dataframe(snapshots)
+    if market == 'indices':
+        # Indices are computed values, not traded instruments: there is no volume and no quote
+        df = df.drop(columns=['Volume', 'VWAP', 'Bid', 'Ask'])
+    return df
+
+
+# Get the day's top gainers or losers ('gainers' or 'losers') of a market
+def get_market_movers_as_df(direction, api_key, market='stocks'):
+    try:
+        snapshots = get_client(api_key).get_market_movers(direction, market=market)
+    except Exception:
+        logger.error(f"Failed to retrieve top {market} {direction}")
+        raise
+    return create_snapshots_dataframe(snapshots)
+
+
+# Convert an amount between two currencies at the last forex quote (not cached by Streamlit so every request sees the latest rate)
+def get_currency_conversion(from_currency, to_currency, amount, api_key):
+    try:
+        return get_client(api_key).get_currency_conversion(from_currency, to_currency, amount)
+    except Exception:
+        logger.error(f"Failed to convert {amount} {from_currency} to {to_currency}")
+        raise
+
+
+# Get stock exchange names by ID from the reference data, falling back to the built-in table when it is unavailable
+@st.cache_data(ttl=24 * 60 * 60, show_spinner=False)
+def get_exchange_names(api_key):
+    try:
+        exchanges = get_client(api_key).get_exchanges('stocks')
+    except Exception as e:
+        logger.warning(f"Failed to retrieve exchanges, using the built-in table: {e}")
+        return dict(EXCHANGES)
+    return {**EXCHANGES, **{exchange.id: exchange.name for exchange in exchanges}}
+
+
+# Get the MICs of the stock exchanges, for filtering tickers by primary exchange (empty when unavailable)
+@st.cache_data(ttl=24 * 60 * 60, show_spinner=False)
+def get_exchange_mics(api_key):
+    try:
+        exchanges = get_client(api_key).get_exchanges('stocks')
+    except Exception as e:
+        logger.warning(f"Failed to retrieve exchanges: {e}")
+        return []
+    return sorted({exchange.mic for exchange in exchanges if exchange.mic and exchange.type == 'exchange'})
+
+
+# Get stock trade condition names by ID from the reference data, falling back to the built-in table when it is unavailable
+@st.cache_data(ttl=24 * 60 * 60, show_spinner=False)
+def get_trade_condition_names(api_key):
+    try:
+        conditions = get_client(api_key).get_conditions('stocks', 'trade')
+    except Exception as e:
+        logger.warning(f"Failed to retrieve trade conditions, using the built-in table: {e}")
+        return dict(TRADE_CONDITIONS)
+    return {**TRADE_CONDITIONS, **{condition.id: condition.name for condition in conditions}}
+
+
+# Get whether markets are open right now (None when the status is unavailable)
+@st.cache_data(ttl=60, show_spinner=False)
+def get_market_status(api_key):
+    try:
+        return get_client(api_key).get_market_status()
+    except Exception as e:
+        logger.warning(f"Failed to retrieve market status: {e}")
+        return None
+
+
+# Get the upcoming holidays and early closes of NYSE and Nasdaq, soonest first
+@st.cache_data(ttl=60 * 60, show_spinner=False)
+def get_upcoming_holidays(api_key):
+    try:
+        holidays = get_client(api_key).get_upcoming_holidays()
+    except Exception as e:
+        logger.warning(f"Failed to retrieve upcoming market holidays: {e}")
+        return []
+    return sorted((holiday for holiday in holidays if holiday.exchange in ('NYSE', 'NASDAQ')), key=lambda holiday: holiday.date)
+
+
+# Get the announced full-day market closures as {date: name}, to complement the built-in holiday calendar
+def get_market_holidays(api_key):
+    return {date.fromisoformat(holiday.date): holiday.name for holiday in get_upcoming_holidays(api_key) if holiday.status == 'closed'}
+
+
+# Get the trades of a ticker between two Unix nanosecond timestamps, with exchange and condition codes decoded
+@st.cache_data(ttl=1800, max_entries=10, show_spinner='Fetching trades from API...')
+def get_trades_as_df(ticker, from_timestamp, to_timestamp, api_key, limit=None):
+    try:
+        trades = get_client(api_key).get_trades(ticker, from_timestamp, to_timestamp, limit)
+    except Exception:
+        logger.error(f"Failed to retrieve trades for {ticker} from {from_timestamp} to {to_timestamp}")
+        raise
+    if not trades:
+        logger.warning(f"No trades found for {ticker} from {from_timestamp} to {to_timestamp}")
+        return pd.DataFrame()
+    exchanges, conditions = get_exchange_names(api_key), get_trade_condition_names(api_key)
+    df = pd.DataFrame({
+        'Time': pd.to_datetime([trade.sip_timestamp for trade in trades], unit='ns', utc=True).tz_convert('US/Eastern'),
+        'Price': [trade.price for trade in trades],
+        'Size': [trade.size for trade in trades],
+        'Exchange': [exchange_name(trade.exchange, exchanges) for trade in trades],
+        'Conditions': [condition_names(trade.conditions, conditions) for trade in trades],
+        'Sequence Number': [trade.sequence_number for trade in trades],
+    }).set_index('Time')
+    return df.astype({'Price': 'float64', 'Size': 'float64', 'Sequence Number': 'Int64'})
+
+
+# Get the NBBO quotes of a ticker between two Unix nanosecond timestamps, with the bid-ask spread
+@st.cache_data(ttl=1800, max_entries=10, show_spinner='Fetching quotes from API...')
+def get_quotes_as_df(ticker, from_timestamp, to_timestamp, api_key, limit=None):
+    try:
+        quotes = get_client(api_key).get_quotes(ticker, from_timestamp, to_timestamp, limit)
+    except Exception:
+        logger.error(f"Failed to retrieve quotes for {ticker} from {from_timestamp} to {to_timestamp}")
+        raise
+    if not quotes:
+        logger.warning(f"No quotes found for {ticker} from {from_timestamp} to {to_timestamp}")
+        return pd.DataFrame()
+    exchanges = get_exchange_names(api_key)
+    df = pd.DataFrame({
+        'Time': pd.to_datetime([quote.sip_timestamp for quote in quotes], unit='ns', utc=True).tz_convert('US/Eastern'),
+        'Bid': [quote.bid_price for quote in quotes],
+        'Bid Size': [quote.bid_size for quote in quotes],
+        'Bid Exchange': [exchange_name(quote.bid_exchange, exchanges) for quote in quotes],
+        'Ask': [quote.ask_price for quote in quotes],
+        'Ask Size': [quote.ask_size for quote in quotes],
+        'Ask Exchange': [exchange_name(quote.ask_exchange, exchanges) for quote in quotes],
+    }).set_index('Time')
+    df = df.astype({'Bid': 'float64', 'Bid Size': 'float64', 'Ask': 'float64', 'Ask Size': 'float64'})
+    df['Spread'] = df['Ask'] - df['Bid']
+    return df
+
+
+# Get the upcoming expiration dates of the options on an underlying ticker
+@st.cache_data(ttl=60 * 60, max_entries=100, show_spinner='Fetching options contracts from API...')
+def get_option_expirations(underlying_ticker, api_key):
+    try:
+        contracts = get_client(api_key).get_options_contracts(underlying_ticker)
+    except Exception:
+        logger.error(f"Failed to retrieve options contracts for {underlying_ticker}")
+        raise
+    return sorted({contract.expiration_date for contract in contracts})
+
+
+# Columns of the options chain table, from OptionSnapshot fields
+OPTION_CHAIN_COLUMNS = {
+    'ticker': 'Contract', 'contract_type': 'Type', 'expiration_date': 'Expiration', 'strike_price': 'Strike',
+    'last_price': 'Last', 'bid': 'Bid', 'ask': 'Ask', 'midpoint': 'Mid', 'implied_volatility': 'IV',
+    'delta': 'Delta', 'gamma': 'Gamma', 'theta': 'Theta', 'vega': 'Vega', 'open_interest': 'Open Interest',
+    'day_volume': 'Volume', 'break_even_price': 'Break Even', 'underlying_price': 'Underlying Price', 'underlying_updated': 'Underlying Updated',
+}
+
+
+# Create a table of options contract snapshots indexed by contract, ordered by type and strike
+def create_option_chain_dataframe(snapshots):
+    if not snapshots:
+        return pd.DataFrame(columns=list(OPTION_CHAIN_COLUMNS.values())[1:]).rename_axis('Contract')
+    df = pd.DataFrame([asdict(snapshot) for snapshot in snapshots])[list(OPTION_CHAIN_COLUMNS)].rename(columns=OPTION_CHAIN_COLUMNS)
+    numeric = ['Strike', 'Last', 'Bid', 'Ask', 'Mid', 'IV', 'Delta', 'Gamma', 'Theta', 'Vega', 'Open Interest', 'Volume', 'Break Even', 'Underlying Price']
+    df[numeric] = df[numeric].astype('float64')
+    # The midpoint is only reported with the quote, fill it in when both sides are known
+    df['Mid'] = df['Mid'].fillna((df['Bid'] + df['Ask']) / 2)
+    df['Underlying Updated'] = pd.to_datetime(df['Underlying Updated'], unit='ns', utc=True).dt.tz_convert('US/Eastern')
+    return df.sort_values(['Type', 'Strike']).set_index('Contract')
+
+
+# Get the options chain of an underlying ticker for one expiration date (not cached by Streamlit so every request sees new data)
+def get_option_chain_as_df(underlying_ticker, expiration_date, api_key):
+    try:
+        snapshots = get_client(api_key).get_option_chain(underlying_ticker, expiration_date)
+    except Exception:
+        logger.error(f"Failed to retrieve the {underlying_ticker} options chain expiring {expiration_date}")
+        raise
+    if not snapshots:
+        logger.warning(f"No options found for {underlying_ticker} expiring {expiration_date}")
+    return create_option_chain_dataframe(snapshots)
+
+
+# Create a table of tickers indexed by symbol
+def create_tickers_dataframe(tickers):
+    columns = {'ticker': 'Ticker', 'name': 'Name', 'market': 'Market', 'type': 'Type', 'primary_exchange': 'Primary Exchange',
+               'currency_name': 'Currency', 'active': 'Active', 'cik': 'CIK'}
+    if not tickers:
+        return pd.DataFrame(columns=list(columns.values())[1:]).rename_axis('Ticker')
+    return pd.DataFrame([asdict(ticker) for ticker in tickers])[list(columns)].rename(columns=columns).set_index('Ticker')
+
+
+# Search tickers by symbol or company name
+@st.cache_data(ttl=1800, max_entries=100, show_spinner='Searching tickers...')
+def search_tickers_as_df(search, market, ticker_type, exchange, active, limit, api_key):
+    try:
+        tickers = get_client(api_key).search_tickers(search, market, ticker_type, exchange, active, limit)
+    except Exception:
+        logger.error(f"Ticker search for '{search}' failed")
+        raise
+    logger.info(f"Ticker search for '{search}' returned {len(tickers)} tickers")
+    return create_tickers_dataframe(tickers)
+
+
+# Background loads of the ticker lists, by API key and market: the thread and when it last failed
+ticker_universe_loads = {}
+ticker_universe_lock = threading.Lock()
+
+# Seconds before a failed ticker list load is tried again
+TICKER_UNIVERSE_RETRY = 10 * 60
+
+
+# Load the ticker list of a market into the local cache on a background thread, unless it is already loading or failed recently
+def load_ticker_universe_in_background(api_key, market='stocks'):
+    client = get_client(api_key)
+    if client.offline:
+        return
+    with ticker_universe_lock:
+        load = ticker_universe_loads.get((api_key, market))
+        if load and (load['thread'].is_alive() or time.time() - load['failed_at'] < TICKER_UNIVERSE_RETRY):
+            return
+        load = {'thread': None, 'failed_at': 0.0}
+
+        def run():
+            try:
+                tickers = client.get_ticker_universe(market)
+                logger.info(f"Loaded {len(tickers)} {market} tickers in the background")
+            except Exception as e:
+                logger.warning(f"Failed to load the {market} ticker list: {e}")
+                load['failed_at'] = time.time()
+
+        load['thread'] = threading.Thread(target=run, name=f"ticker-list-{market}", daemon=True)
+        ticker_universe_loads[(api_key, market)] = load
+        load['thread'].start()
+
+
+# Get the ticker list of a market for the ticker pickers once it is in the local cache
+# The list takes several pages at the free tier's rate limit, so the first call starts loading it in the background and
+# returns an empty frame, on which the pickers fall back to free text
+def get_ticker_universe_as_df(api_key, market='stocks'):
+    try:
+        return get_cached_ticker_universe_as_df(api_key, market)
+    except CacheMissError:
+        load_ticker_universe_in_background(api_key, market)
+        return pd.DataFrame()
+
+
+# Ticker list of a market from the local cache (CacheMissError is raised, and so not cached here, until it is loaded)
+@st.cache_data(ttl=24 * 60 * 60, show_spinner=False)
+def get_cached_ticker_universe_as_df(api_key, market='stocks'):
+    return create_tickers_dataframe(get_client(api_key).get_ticker_universe(market, cached_only=True))
+
+
+# Reload the ticker list of a market from the API and return the number of tickers
+def refresh_ticker_universe(api_key, market='stocks'):
+    tickers = get_client(api_key).get_ticker_universe(market, refresh=True)
+    get_cached_ticker_universe_as_df.clear()
+    logger.info(f"Refreshed the {market} ticker list with {len(tickers)} tickers")
+    return len(tickers)
 
 
 # Get financials data from Polygon API
 @st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
 def get_financials_as_df(ticker, limit, api_key, timeframe=None):
-    url = f"https://api.polygon.io/vX/reference/financials?ticker={ticker}&limit={limit}&apiKey={api_key}"
-    if timeframe:
-        url += f"&timeframe={timeframe}"
-    logger.info(f"Requesting financials data for {ticker} with limit {limit} and timeframe {timeframe}")
-    response = requests.get(url)
-    if response.status_code == 200:
-        data = response.json()['results']
-        logger.info(f"Successfully retrieved financials data for {ticker}. Number of records: {len(data)}")
-        return data
-    else:
-        logger.error(f"Failed to retrieve financials data for {ticker}. Status code: {response.status_code}, Response: {response.text}")
+    try:
+        data = get_client(api_key).get_financials(ticker, limit, timeframe=timeframe)
+    except Exception as e:
+        logger.error(f"Failed to retrieve financials data for {ticker}: {e}")
         return []
+    logger.info(f"Successfully retrieved financials data for {ticker}. Number of records: {len(data)}")
+    return data
 
 
 # Create a dataframe from the financials data
@@ -64,27 +484,20 @@ def create_financials_dataframe(data):
 
     for item in data:
         record = {
-            "CIK": item.get("cik"),
-            "Company Name": item.get("company_name"),
-            "Fiscal Year": item.get("fiscal_year"),
-            "Fiscal Period": item.get("fiscal_period"),
-            "Start Date": item.get("start_date"),
-            "End Date": item.get("end_date"),
-            "Filing Date": item.get("filing_date"),
+            "CIK": item.cik,
+            "Company Name": item.company_name,
+            "Fiscal Year": item.fiscal_year,
+            "Fiscal Period": item.fiscal_period,
+            "Start Date": item.start_date,
+            "End Date": item.end_date,
+            "Filing Date": item.filing_date,
         }
-        
-        financials = item.get('financials', {})
-        for section, section_data in financials.items():
+
+        for section, section_data in item.financials.items():
             for key, value in section_data.items():
-                label = value.get("label")
-                if label:
-                    record[label] = value.get("value")
-
-        # Free Cash Flow calculation
-        net_cash_flow_op = record.get("Net Cash Flow From Operating Activities", 0)
-        net_cash_flow_inv = record.get("Net Cash Flow From Investing Activities", 0)
-        record["Free Cash Flow"] = net_cash_flow_op + net_cash_flow_inv
-        
+                if value.label:
+                    record[value.label] = value.value
+
         records.append(record)
 
     if records:
@@ -92,106 +505,110 @@ def create_financials_dataframe(data):
     else:
         logger.warning("No records were created for the dataframe.")
 
-    df = pd.DataFrame(records)
-    df = format_with_comma(df)  # Apply comma formatting for entire DataFrame
+    # Ratios, free cash flow and growth rates, one row per report like the records
+    metrics = financial_metrics(data)
+    df = pd.concat([pd.DataFrame(records), metrics], axis=1)
+    for col in ["Start Date", "End Date", "Filing Date"]:
+        if col in df.columns:
+            df[col] = pd.to_datetime(df[col])
     columns_order = [
         "CIK", "Company Name", "Fiscal Year", "Fiscal Period", "Start Date", "End Date", "Filing Date",
         "Revenues", "Gross Profit", "Operating Income/Loss", "Income/Loss From Continuing Operations Before Tax",
         "Net Income/Loss", "Basic Earnings Per Share", "Diluted Earnings Per Share", "Assets",
         "Current Assets", "Noncurrent Assets", "Liabilities", "Current Liabilities", "Noncurrent Liabilities",
-        "Equity", "Net Cash Flow From Operating Activities", "Net Cash Flow From Investing Activities", 
-        "Net Cash Flow From Financing Activities", "Free Cash Flow"
+        "Equity", "Net Cash Flow From Operating Activities", "Net Cash Flow From Investing Activities",
+        "Net Cash Flow From Financing Activities", *metrics.columns
     ]
     df = df[[col for col in columns_order if col in df.columns]]
 
     logger.info(f"Dataframe creation completed. Number of rows: {df.shape[0]}")
     return df
 
+# Get the tickers Polygon relates to a company (empty when there are none)
+@st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
+def get_related_companies(ticker, api_key):
+    try:
+        related = get_client(api_key).get_related_companies(ticker)
+    except Exception as e:
+        logger.error(f"Failed to retrieve related companies for {ticker}: {e}")
+        return []
+    logger.info(f"Successfully retrieved {len(related)} related companies for {ticker}.")
+    return related
+
+
+# Headline items, metrics and growth rates of the latest report of each ticker, one row per ticker
+# Five reports are fetched so the latest one has a report a year earlier to compute year-over-year growth
+def get_peer_metrics_as_df(tickers, timeframe, api_key):
+    rows = {}
+    for ticker in tickers:
+        reports = get_financials_as_df(ticker, 5, api_key, timeframe=timeframe)
+        if not reports:
+            logger.warning(f"No {timeframe} financials found for {ticker}, leaving it out of the peer comparison")
+            continue
+        rows[ticker] = metrics_history(reports).iloc[-1]
+    if not rows:
+        return pd.DataFrame()
+    # Rows mix text and numbers, so restore the numeric dtypes column by column
+    return pd.DataFrame.from_dict(rows, orient='index').infer_objects().rename_axis('Ticker')
+
+
 # Get company details from Polygon API
 @st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
 def get_company_details(ticker, api_key):
-    logger.info(f"Requesting company details for ticker: {ticker}")
-    url = f"https://api.polygon.io/v3/reference/tickers/{ticker}?apiKey={api_key}"
-    response = requests.get(url)
-    if response.status_code == 200:
-        data = response.json().get('results', {})
-        if data:
-            logger.info(f"Successfully retrieved company details for {ticker}.")
-        else:
-            logger.warning(f"Company details for {ticker} were found, but no data was returned.")
-        # Convert the data to a dataframe
-        details_df = pd.DataFrame([data])
-        return details_df.transpose()
+    try:
+        details = get_client(api_key).get_ticker_details(ticker)
+    except Exception as e:
+        logger.error(f"Failed to retrieve company details for {ticker}: {e}")
+        raise
+    data = {key: value for key, value in asdict(details).items() if value is not None}
+    if len(data) > 1:
+        logger.info(f"Successfully retrieved company details for {ticker}.")
     else:
-        logger.error(f"Failed to retrieve company details for {ticker}: HTTP {response.status_code}")
-        raise Exception(f"Failed to retrieve company details: {response.status_code}")
-    
+        logger.warning(f"Company details for {ticker} were found, but no data was returned.")
+    # Convert the data to a dataframe
+    details_df = pd.DataFrame([data])
+    return details_df.transpose()
+
 # Get stock splits data from Polygon API
 @st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
-def get_stock_splits(ticker=None, limit=50, **date_filters):
-    logger.info(f"Requesting stock splits data for ticker: {ticker if ticker else 'All Tickers'} with limit: {limit}")
-    # Base URL
-    base_url = f'https://api.polygon.io/v3/reference/splits?limit={limit}&apiKey={API_KEY}'
-    
-    # Add ticker to the URL if provided
-    if ticker:
-        base_url += f'&ticker={ticker}'
-    
-    # Add date filters to the URL
-    for key, value in date_filters.items():
-        if value:  # Only add the filter if the value is not None
-            base_url += f'&execution_date.{key}={value}'
-
-    response = requests.get(base_url)
-    if response.status_code == 200:
-        data = response.json().get('results', [])
-        if data:
-            logger.info(f"Successfully retrieved stock splits data for {ticker if ticker else 'All Tickers'}.")
-            df = pd.DataFrame(data)[['ticker', 'execution_date', 'split_from', 'split_to']]
-            df.columns = ['Ticker', 'Execution Date', 'Split From', 'Split To']
-            df['Adjustment Factor'] = df['Split From'] / df['Split To']
-            df['Adjustment Factor'] = df['Adjustment Factor'].apply(lambda x: f"{x:.10f}")
-            return df
-        else:
-            logger.warning(f"Stock splits data for {ticker if ticker else 'All Tickers'} was found, but no data was returned.")
-            return pd.DataFrame(columns=['Ticker', 'Execution Date', 'Split From', 'Split To', 'Adjustment Factor'])
+def get_stock_splits(ticker, limit, api_key, **date_filters):
+    try:
+        data = get_client(api_key).get_stock_splits(ticker, limit, **date_filters)
+    except Exception:
+        logger.error(f"Failed to retrieve stock splits data for {ticker if ticker else 'All Tickers'}")
+        raise
+    if data:
+        logger.info(f"Successfully retrieved stock splits data for {ticker if ticker else 'All Tickers'}. Number of records: {len(data)}")
+        df = pd.DataFrame([asdict(split) for split in data])[['ticker', 'execution_date', 'split_from', 'split_to']]
+        df.columns = ['Ticker', 'Execution Date', 'Split From', 'Split To']
+        df['Execution Date'] = pd.to_datetime(df['Execution Date'])
+        df['Adjustment Factor'] = df['Split From'] / df['Split To']
+        return df
     else:
-        logger.error(f"Failed to retrieve stock splits data for {ticker if ticker else 'All Tickers'}: HTTP {response.status_code}")
-        raise Exception(f"API request failed with status code {response.status_code}: {response.text}")
+        logger.warning(f"Stock splits data for {ticker if ticker else 'All Tickers'} was found, but no data was returned.")
+        return pd.DataFrame(columns=['Ticker', 'Execution Date', 'Split From', 'Split To', 'Adjustment Factor'])
 
 # Get dividends data from Polygon API
 @st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
 def get_dividends_data(ticker, limit, api_key):
-    logger.info(f"Requesting dividends data for ticker: {ticker} with limit: {limit}")
-    url = f"https://api.polygon.io/v3/reference/dividends?ticker={ticker}&limit={limit}&apiKey={api_key}"
-    response = requests.get(url)
-    if response.status_code == 200:
-        data = response.json().get('results', [])
-        if data:
-            logger.info(f"Successfully retrieved dividends data for {ticker}.")
-            return data
-        else:
-            logger.warning(f"Dividends data for {ticker} was found, but no data was returned.")
-            return []
+    try:
+        data = get_client(api_key).get_dividends(ticker, limit)
+    except Exception:
+        logger.error(f"Failed to retrieve dividends data for {ticker}")
+        raise
+    if data:
+        logger.info(f"Successfully retrieved dividends data for {ticker}. Number of records: {len(data)}")
+        return data
     else:
-        logger.error(f"Failed to retrieve dividends data for {ticker}: HTTP {response.status_code}")
-        raise Exception(f"API request failed with status code {response.status_code}: {response.text}")
-    
+        logger.warning(f"Dividends data for {ticker} was found, but no data was returned.")
+        return []
 
-# Get news from Polygon API 
+
+# Get news from Polygon API
 @st.cache_data(ttl=1800, max_entries=100, show_spinner='Fetching data from API...')
-def get_news(ticker=None, limit=5, api_key=API_KEY):
-    # Use the ticker-specific news URL if ticker is provided
-    if ticker:
-        url = f"https://api.polygon.io/v2/reference/news?ticker={ticker}&limit={limit}&apiKey={api_key}"
-    else:
-        # Use the general news URL if no ticker is provided
-        url = f"https://api.polygon.io/v2/reference/news?limit={limit}&apiKey={api_key}"
-    
-    response = requests.get(url)
-    if response.status_code == 200:
-        news_data = response.json().get('results', [])
-        return news_data
-    else:
-        logger.error(f"Failed to retrieve news: {response.status_code}")
+def get_news(api_key, ticker=None, limit=5):
+    try:
+        return get_client(api_key).get_news(ticker, limit)
+    except Exception as e:
+        logger.error(f"Failed to retrieve news: {e}")
         return []
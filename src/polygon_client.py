@@ -0,0 +1,462 @@
+import time
+from datetime import date, datetime
+import config.api_config as api_config
+import config.log_config
+from cache import CacheMissError, MARKET_TIMEZONE, UTC
+from http_client import get_http_session
+from markets import get_market, market_of
+from models import Aggregate, IndicatorValue, DailyOpenClose, TickerSnapshot, CurrencyConversion, Trade, Quote, Exchange, Condition, MarketStatus, MarketHoliday, OptionsContract, OptionSnapshot, TickerDetails, FinancialReport, StockSplit, Dividend, NewsArticle
+
+# Initialize the logger
+logger = config.log_config.setup_logging()
+
+# Maximum page size accepted by each Polygon endpoint
+MAX_PAGE_SIZE = {
+    'aggs': 50000,
+    'financials': 100,
+    'splits': 1000,
+    'dividends': 1000,
+    'news': 1000,
+    'trades': 50000,
+    'quotes': 50000,
+    'conditions': 1000,
+    'tickers': 1000,
+    'options_contracts': 1000,
+    'option_chain': 250,
+    'index_snapshot': 250,
+    'indicators': 5000,
+}
+
+
+# Most tickers kept in the ticker list of a market: five pages, or a minute of the free tier's rate limit
+TICKER_UNIVERSE_LIMIT = 5000
+
+# Timespans whose single-unit bars are stored individually and topped up incrementally
+# Larger timespans and multiples such as 5-minute bars are cached as whole responses, since a top-up starting
+# mid-range would put its buckets out of line with the stored ones
+INCREMENTAL_TIMESPANS = {'second', 'minute', 'hour', 'day'}
+
+
+# Parse a 'YYYY-MM-DD' string or date into a date
+def parse_date(value):
+    return value if isinstance(value, date) else date.fromisoformat(str(value)[:10])
+
+
+# Error raised when the Polygon API returns a non-200 response
+class PolygonAPIError(Exception):
+    def __init__(self, status_code, text):
+        super().__init__(f"API request failed with status code {status_code}: {text}")
+        self.status_code = status_code
+        self.text = text
+
+
+# Client for the Polygon REST API, usable with or without Streamlit
+# With a ResponseCache responses are persisted on disk; offline mode serves everything from that cache
+class PolygonClient:
+    def __init__(self, api_key, base_url=api_config.BASE_URL, session=None, cache=None, offline=False):
+        if offline and cache is None:
+            raise ValueError("Offline mode requires a cache")
+        if not api_key and not offline:
+            raise ValueError("A Polygon API key is required")
+        self.api_key = api_key
+        self.base_url = base_url.rstrip('/')
+        self.session = session or get_http_session()
+        self.cache = cache
+        self.offline = offline
+
+    # Send a GET request and return the decoded JSON body
+    def _get(self, url, params=None):
+        params = dict(params or {})
+        params['apiKey'] = self.api_key
+        response = self.session.get(url, params=params)
+        if response.status_code != 200:
+            logger.error(f"Request to {url} failed with status code {response.status_code}: {response.text}")
+            raise PolygonAPIError(response.status_code, response.text)
+        return response.json()
+
+    # Yield the results of each page, following the next_url cursor
+    # field picks the list out of endpoints whose results are an object (e.g. 'values' for indicators)
+    def _iter_pages(self, path, params, field=None):
+        url = f"{self.base_url}{path}"
+        while url:
+            payload = self._get(url, params)
+            results = payload.get('results', [])
+            yield (results or {}).get(field, []) if field else results
+            # next_url already carries the query, only the API key is added on later pages
+            url = payload.get('next_url')
+            params = None
+
+    # Follow the next_url cursor until the record budget is reached (None means all pages)
+    def _paginate(self, path, params, max_records=None, field=None):
+        results = []
+        page = 0
+        for page, items in enumerate(self._iter_pages(path, params, field), start=1):
+            results.extend(items)
+            if max_records is not None and len(results) >= max_records:
+                break
+        logger.info(f"Retrieved {len(results)} records from {path} over {page} page(s)")
+        return results if max_records is None else results[:max_records]
+
+    # Serve a request from the cache when possible, otherwise fetch it and store the result
+    # refresh skips the cached response (except in offline mode, where the cache is all there is)
+    def _cached(self, endpoint, params, fetch, refresh=False):
+        if self.cache is None:
+            return fetch()
+        payload = None if refresh and not self.offline else self.cache.get(endpoint, params, allow_stale=self.offline)
+        if payload is not None:
+            logger.info(f"Serving {endpoint} {params} from cache")
+            return payload
+        if self.offline:
+            raise CacheMissError(f"No cached {endpoint} data for {params} (offline mode)")
+        payload = fetch()
+        self.cache.put(endpoint, params, payload)
+        return payload
+
+    # Fetch raw aggregate bars from the API (limit caps the number of bars returned)
+    def _fetch_aggregates(self, ticker, from_date, to_date, timespan, multiplier, adjusted, sort='asc', limit=None):
+        path = f"/v2/aggs/ticker/{ticker}/range/{multiplier}/{timespan}/{from_date}/{to_date}"
+        params = {'adjusted': 'true' if adjusted else 'false', 'sort': sort, 'limit': min(limit or MAX_PAGE_SIZE['aggs'], MAX_PAGE_SIZE['aggs'])}
+        return self._paginate(path, params, max_records=limit)
+
+    # Drop the split-adjusted bars of a series when the ticker split after they were stored, so they are fetched again
+    # The check runs at most once a day per series
+    def _drop_bars_adjusted_before_split(self, ticker, series):
+        stored_at = self.cache.bars_stored_at(series)
+        if stored_at is None:
+            return
+        stored_on = datetime.fromtimestamp(stored_at, MARKET_TIMEZONE).date()
+        today = datetime.now(MARKET_TIMEZONE).date()
+        checked_on = self.cache.bars_checked_on(series)
+        if stored_on >= today or checked_on == today:
+            return
+        since = max(stored_on, checked_on) if checked_on else stored_on
+        try:
+            splits = self.get_stock_splits(ticker, limit=MAX_PAGE_SIZE['splits'], gte=since.isoformat(), lte=today.isoformat())
+        except PolygonAPIError as e:
+            logger.warning(f"Could not check {ticker} for splits since {since}, keeping the cached bars of {series}: {e}")
+            return
+        if splits:
+            logger.info(f"{ticker} split on {splits[0].execution_date}, dropping the cached bars of {series} stored on {stored_on}")
+            self.cache.clear_bars(series)
+        else:
+            self.cache.set_bars_checked_on(series, today)
+
+    # Get aggregate bars for a ticker, e.g. multiplier=5 and timespan='minute' for 5-minute bars
+    # Timespans: second, minute, hour, day, week, month, quarter, year; sort is 'asc' or 'desc'
+    # Cached bars are reused and only missing date ranges are fetched; forex and crypto bars are never split adjusted
+    # Cached split-adjusted bars are fetched again once the ticker splits
+    def get_aggregates(self, ticker, from_date, to_date, timespan='day', multiplier=1, adjusted=True, sort='asc', limit=None):
+        adjusted = adjusted and market_of(ticker).split_adjusted
+        logger.info(f"Requesting aggregates for {ticker} from {from_date} to {to_date} with adjusted={adjusted}, timespan={multiplier} {timespan}, sort={sort} and limit={limit}")
+        if self.cache is None:
+            items = self._fetch_aggregates(ticker, from_date, to_date, timespan, multiplier, adjusted, sort, limit)
+        elif timespan not in INCREMENTAL_TIMESPANS or multiplier != 1 or sort != 'asc' or limit:
+            params = {'ticker': ticker, 'from': str(from_date), 'to': str(to_date), 'timespan': timespan, 'multiplier': multiplier,
+                      'adjusted': adjusted, 'sort': sort, 'limit': limit}
+            items = self._cached('aggs', params, lambda: self._fetch_aggregates(ticker, from_date, to_date, timespan, multiplier, adjusted, sort, limit))
+        else:
+            series = f"{ticker}:{multiplier}:{timespan}:{'adjusted' if adjusted else 'unadjusted'}"
+            start, end = parse_date(from_date), parse_date(to_date)
+            timezone = UTC if market_of(ticker).round_the_clock else MARKET_TIMEZONE
+            if adjusted and not self.offline:
+                self._drop_bars_adjusted_before_split(ticker, series)
+            missing = self.cache.missing_bar_ranges(series, start, end)
+            for gap_start, gap_end in missing:
+                if self.offline:
+                    logger.warning(f"Offline mode: {series} has no cached bars from {gap_start} to {gap_end}")
+                    continue
+                logger.info(f"Topping up {series} from {gap_start} to {gap_end}")
+                bars = self._fetch_aggregates(ticker, gap_start.isoformat(), gap_end.isoformat(), timespan, multiplier, adjusted)
+                self.cache.put_bars(series, gap_start, gap_end, bars, timezone)
+            items = self.cache.get_bars(series, start, end, timezone)
+            if self.offline and missing and not items:
+                raise CacheMissError(f"No cached bars for {series} from {start} to {end} (offline mode)")
+        return [Aggregate.from_api(item) for item in items]
+
+    # Fetch the values of a server-side indicator ('sma', 'ema', 'rsi' or 'macd') between two dates, oldest first
+    # series_type is the price the indicator is computed on ('close', 'open', 'high' or 'low')
+    def _get_indicator(self, indicator, ticker, windows, from_date, to_date, timespan, adjusted, series_type, limit):
+        adjusted = adjusted and market_of(ticker).split_adjusted
+        logger.info(f"Requesting {indicator.upper()} {windows} of {ticker} from {from_date} to {to_date} with timespan={timespan}, adjusted={adjusted} and series_type={series_type}")
+        params = dict(windows, timespan=timespan, adjusted='true' if adjusted else 'false', series_type=series_type, order='asc',
+                      limit=min(limit or MAX_PAGE_SIZE['indicators'], MAX_PAGE_SIZE['indicators']))
+        for key, value in {'timestamp.gte': from_date, 'timestamp.lte': to_date}.items():
+            if value:
+                params[key] = str(value)
+        path = f"/v1/indicators/{indicator}/{ticker}"
+        items = self._cached('indicators', dict(params, indicator=indicator, ticker=ticker, max_records=limit),
+                             lambda: self._paginate(path, params, max_records=limit, field='values'))
+        return [IndicatorValue.from_api(item) for item in items]
+
+    # Get Polygon's simple moving average of a ticker
+    def get_sma(self, ticker, from_date=None, to_date=None, timespan='day', adjusted=True, window=50, series_type='close', limit=None):
+        return self._get_indicator('sma', ticker, {'window': window}, from_date, to_date, timespan, adjusted, series_type, limit)
+
+    # Get Polygon's exponential moving average of a ticker
+    def get_ema(self, ticker, from_date=None, to_date=None, timespan='day', adjusted=True, window=50, series_type='close', limit=None):
+        return self._get_indicator('ema', ticker, {'window': window}, from_date, to_date, timespan, adjusted, series_type, limit)
+
+    # Get Polygon's relative strength index of a ticker
+    def get_rsi(self, ticker, from_date=None, to_date=None, timespan='day', adjusted=True, window=14, series_type='close', limit=None):
+        return self._get_indicator('rsi', ticker, {'window': window}, from_date, to_date, timespan, adjusted, series_type, limit)
+
+    # Get Polygon's MACD of a ticker, with its signal line and histogram
+    def get_macd(self, ticker, from_date=None, to_date=None, timespan='day', adjusted=True, short_window=12, long_window=26, signal_window=9,
+                 series_type='close', limit=None):
+        windows = {'short_window': short_window, 'long_window': long_window, 'signal_window': signal_window}
+        return self._get_indicator('macd', ticker, windows, from_date, to_date, timespan, adjusted, series_type, limit)
+
+    # Get the previous trading day's bar for a ticker (None when Polygon has none)
+    def get_previous_close(self, ticker, adjusted=True):
+        adjusted = adjusted and market_of(ticker).split_adjusted
+        logger.info(f"Requesting previous close for {ticker} with adjusted={adjusted}")
+        params = {'adjusted': 'true' if adjusted else 'false'}
+        payload = self._cached('prev_close', dict(params, ticker=ticker), lambda: self._get(f"{self.base_url}/v2/aggs/ticker/{ticker}/prev", params))
+        results = payload.get('results') or []
+        return Aggregate.from_api(results[0]) if results else None
+
+    # Get the open, close, pre-market and after-hours prices of a ticker on one day
+    def get_daily_open_close(self, ticker, day, adjusted=True):
+        logger.info(f"Requesting daily open/close for {ticker} on {day} with adjusted={adjusted}")
+        params = {'adjusted': 'true' if adjusted else 'false'}
+        payload = self._cached('open_close', dict(params, ticker=ticker, date=str(day)), lambda: self._get(f"{self.base_url}/v1/open-close/{ticker}/{day}", params))
+        return DailyOpenClose.from_api(payload)
+
+    # Get the daily bars of every ticker of a market ('stocks', 'fx' or 'crypto') on one day (empty when the market was closed)
+    def get_grouped_daily(self, day, adjusted=True, include_otc=False, market='stocks'):
+        market = get_market(market)
+        if market.grouped_path is None:
+            raise ValueError(f"There are no grouped daily bars for {market.label.lower()}")
+        adjusted = adjusted and market.split_adjusted
+        logger.info(f"Requesting grouped daily {market.name} bars for {day} with adjusted={adjusted} and include_otc={include_otc}")
+        params = {'adjusted': 'true' if adjusted else 'false'}
+        if market.name == 'stocks':
+            params['include_otc'] = 'true' if include_otc else 'false'
+        payload = self._cached('grouped_daily', dict(params, date=str(day), market=market.name),
+                               lambda: self._get(f"{self.base_url}/v2/aggs/grouped/{market.grouped_path}/{day}", params))
+        return [Aggregate.from_api(item) for item in payload.get('results') or []]
+
+    # Get snapshots of several tickers, or of the whole market ('stocks', 'fx', 'crypto' or 'indices') when tickers is empty
+    def get_snapshots(self, tickers=None, include_otc=False, market='stocks'):
+        market = get_market(market)
+        logger.info(f"Requesting {market.name} snapshots for {', '.join(tickers) if tickers else 'All Tickers'}")
+        if market.snapshot_path is None:
+            return self._get_index_snapshots(tickers)
+        params = {'include_otc': 'true' if include_otc else 'false'} if market.name == 'stocks' else {}
+        if tickers:
+            params['tickers'] = ','.join(tickers)
+        payload = self._cached('snapshot', dict(params, market=market.name), lambda: self._get(f"{self.base_url}/v2/snapshot/{market.snapshot_path}/tickers", params))
+        return [TickerSnapshot.from_api(item) for item in payload.get('tickers') or []]
+
+    # Get index snapshots from the v3 endpoint, leaving out the tickers Polygon reports as not found
+    def _get_index_snapshots(self, tickers=None):
+        params = {'limit': MAX_PAGE_SIZE['index_snapshot']}
+        if tickers:
+            params['ticker.any_of'] = ','.join(tickers)
+        items = self._cached('snapshot', dict(params, market='indices'), lambda: self._paginate('/v3/snapshot/indices', params))
+        return [TickerSnapshot.from_index_api(item) for item in items if 'error' not in item]
+
+    # Get the snapshot of one ticker
+    def get_snapshot(self, ticker):
+        logger.info(f"Requesting snapshot for {ticker}")
+        path = market_of(ticker).snapshot_path
+        if path is None:
+            snapshots = self._get_index_snapshots([ticker])
+            if not snapshots:
+                raise PolygonAPIError(404, f"No snapshot found for {ticker}")
+            return snapshots[0]
+        payload = self._cached('snapshot', {'ticker': ticker}, lambda: self._get(f"{self.base_url}/v2/snapshot/{path}/tickers/{ticker}"))
+        return TickerSnapshot.from_api(payload['ticker'])
+
+    # Get the day's top 20 gainers or losers ('gainers' or 'losers') of a market
+    def get_market_movers(self, direction='gainers', include_otc=False, market='stocks'):
+        if direction not in ('gainers', 'losers'):
+            raise ValueError(f"Unknown direction '{direction}', expected 'gainers' or 'losers'")
+        market = get_market(market)
+        if market.snapshot_path is None:
+            raise ValueError(f"There are no market movers for {market.label.lower()}")
+        logger.info(f"Requesting top {market.name} {direction}")
+        params = {'include_otc': 'true' if include_otc else 'false'} if market.name == 'stocks' else {}
+        payload = self._cached('snapshot', dict(params, direction=direction, market=market.name),
+                               lambda: self._get(f"{self.base_url}/v2/snapshot/{market.snapshot_path}/{direction}", params))
+        return [TickerSnapshot.from_api(item) for item in payload.get('tickers') or []]
+
+    # Convert an amount between two currencies (e.g. 'EUR' to 'USD') at the last forex quote
+    def get_currency_conversion(self, from_currency, to_currency, amount=1, precision=4):
+        logger.info(f"Requesting conversion of {amount} {from_currency} to {to_currency}")
+        params = {'amount': amount, 'precision': precision}
+        payload = self._cached('conversion', dict(params, pair=f"{from_currency}/{to_currency}"),
+                               lambda: self._get(f"{self.base_url}/v1/conversion/{from_currency}/{to_currency}", params))
+        return CurrencyConversion.from_api(payload)
+
+    # Get raw trades or quotes of [from_timestamp, to_timestamp) in Unix nanoseconds, oldest first
+    # With a cache every page is written to the tick store as it arrives, so large ranges are not held in memory twice
+    def _get_ticks(self, kind, ticker, from_timestamp, to_timestamp, limit=None):
+        path = f"/v3/{kind}/{ticker}"
+        params = {'timestamp.gte': from_timestamp, 'timestamp.lt': to_timestamp, 'order': 'asc', 'sort': 'timestamp',
+                  'limit': min(limit or MAX_PAGE_SIZE[kind], MAX_PAGE_SIZE[kind])}
+        if self.cache is None:
+            return self._paginate(path, params, max_records=limit)
+        series = f"{kind}:{ticker}"
+        if not self.cache.has_tick_range(series, from_timestamp, to_timestamp):
+            if self.offline:
+                ticks = self.cache.get_ticks(series, from_timestamp, to_timestamp, limit)
+                if not ticks:
+                    raise CacheMissError(f"No cached {kind} for {ticker} from {from_timestamp} to {to_timestamp} (offline mode)")
+                logger.warning(f"Offline mode: serving {len(ticks)} cached {kind} for {ticker}, the range may be incomplete")
+                return ticks
+            count = 0
+            complete = True
+            for page in self._iter_pages(path, params):
+                self.cache.put_ticks(series, page)
+                count += len(page)
+                if limit is not None and count >= limit:
+                    complete = False
+                    break
+            logger.info(f"Stored {count} {kind} for {ticker} from {from_timestamp} to {to_timestamp}")
+            # Ranges cut short by the limit or still in progress are fetched again next time
+            if complete and to_timestamp <= time.time_ns():
+                self.cache.add_tick_range(series, from_timestamp, to_timestamp)
+        return self.cache.get_ticks(series, from_timestamp, to_timestamp, limit)
+
+    # Get the trades of a ticker between two Unix nanosecond timestamps (end excluded)
+    def get_trades(self, ticker, from_timestamp, to_timestamp, limit=None):
+        logger.info(f"Requesting trades for {ticker} from {from_timestamp} to {to_timestamp} with limit {limit}")
+        return [Trade.from_api(item) for item in self._get_ticks('trades', ticker, from_timestamp, to_timestamp, limit)]
+
+    # Get the NBBO quotes of a ticker between two Unix nanosecond timestamps (end excluded)
+    def get_quotes(self, ticker, from_timestamp, to_timestamp, limit=None):
+        logger.info(f"Requesting quotes for {ticker} from {from_timestamp} to {to_timestamp} with limit {limit}")
+        return [Quote.from_api(item) for item in self._get_ticks('quotes', ticker, from_timestamp, to_timestamp, limit)]
+
+    # Get the exchanges of an asset class ('stocks', 'options', 'crypto' or 'fx'), optionally of one locale ('us' or 'global')
+    def get_exchanges(self, asset_class='stocks', locale=None):
+        logger.info(f"Requesting {asset_class} exchanges")
+        params = {'asset_class': asset_class}
+        if locale:
+            params['locale'] = locale
+        payload = self._cached('exchanges', params, lambda: self._get(f"{self.base_url}/v3/reference/exchanges", params))
+        return [Exchange.from_api(item) for item in payload.get('results') or []]
+
+    # Get the trade or quote conditions of an asset class; data_type is e.g. 'trade', 'bbo' or 'nbbo'
+    def get_conditions(self, asset_class='stocks', data_type=None):
+        logger.info(f"Requesting {asset_class} conditions for data type {data_type}")
+        params = {'asset_class': asset_class, 'limit': MAX_PAGE_SIZE['conditions']}
+        if data_type:
+            params['data_type'] = data_type
+        items = self._cached('conditions', params, lambda: self._paginate('/v3/reference/conditions', params))
+        return [Condition.from_api(item) for item in items]
+
+    # Get whether markets are open right now
+    def get_market_status(self):
+        logger.info("Requesting current market status")
+        payload = self._cached('market_status', {}, lambda: self._get(f"{self.base_url}/v1/marketstatus/now"))
+        return MarketStatus.from_api(payload)
+
+    # Get the upcoming holidays and early closes of every exchange
+    def get_upcoming_holidays(self):
+        logger.info("Requesting upcoming market holidays")
+        payload = self._cached('market_holidays', {}, lambda: self._get(f"{self.base_url}/v1/marketstatus/upcoming"))
+        return [MarketHoliday.from_api(item) for item in payload]
+
+    # Get the options contracts of an underlying ticker, optionally for one expiration date and type ('call' or 'put'),
+    # filtered by strike price (gt, gte, lt, lte); expired=True lists contracts that have already expired
+    def get_options_contracts(self, underlying_ticker, expiration_date=None, contract_type=None, expired=False, limit=None, **strike_filters):
+        logger.info(f"Requesting options contracts for {underlying_ticker} expiring {expiration_date or 'any day'} with type={contract_type} and expired={expired}")
+        params = {'underlying_ticker': underlying_ticker, 'expired': 'true' if expired else 'false', 'sort': 'expiration_date', 'order': 'asc',
+                  'limit': min(limit or MAX_PAGE_SIZE['options_contracts'], MAX_PAGE_SIZE['options_contracts'])}
+        for key, value in {'expiration_date': expiration_date, 'contract_type': contract_type}.items():
+            if value:
+                params[key] = str(value)
+        for key, value in strike_filters.items():
+            if value is not None:
+                params[f'strike_price.{key}'] = value
+        items = self._cached('options_contracts', dict(params, max_records=limit), lambda: self._paginate('/v3/reference/options/contracts', params, max_records=limit))
+        return [OptionsContract.from_api(item) for item in items]
+
+    # Get the snapshot of every contract in an options chain, with greeks, implied volatility, open interest and the last quote
+    def get_option_chain(self, underlying_ticker, expiration_date=None, contract_type=None, **strike_filters):
+        logger.info(f"Requesting the {underlying_ticker} options chain expiring {expiration_date or 'any day'} with type={contract_type}")
+        params = {'limit': MAX_PAGE_SIZE['option_chain']}
+        for key, value in {'expiration_date': expiration_date, 'contract_type': contract_type}.items():
+            if value:
+                params[key] = str(value)
+        for key, value in strike_filters.items():
+            if value is not None:
+                params[f'strike_price.{key}'] = value
+        items = self._cached('option_chain', dict(params, underlying=underlying_ticker), lambda: self._paginate(f"/v3/snapshot/options/{underlying_ticker}", params))
+        return [OptionSnapshot.from_api(item) for item in items]
+
+    # Search tickers by symbol or company name, filtered by market ('stocks', 'otc', 'crypto', 'fx' or 'indices'),
+    # type (e.g. 'CS' or 'ETF'), primary exchange MIC (e.g. 'XNAS') and active status
+    def search_tickers(self, search=None, market='stocks', ticker_type=None, exchange=None, active=True, limit=100):
+        logger.info(f"Searching tickers for '{search}' in {market} with type={ticker_type}, exchange={exchange}, active={active} and limit={limit}")
+        params = {'market': market, 'active': 'true' if active else 'false', 'sort': 'ticker', 'order': 'asc', 'limit': min(limit, MAX_PAGE_SIZE['tickers'])}
+        for key, value in {'search': search, 'type': ticker_type, 'exchange': exchange}.items():
+            if value:
+                params[key] = value
+        items = self._cached('ticker_search', dict(params, max_records=limit), lambda: self._paginate('/v3/reference/tickers', params, max_records=limit))
+        return [TickerDetails.from_api(item) for item in items]
+
+    # Get the active tickers of a market in ticker order, up to limit, kept in the local cache until it expires or is refreshed
+    # cached_only serves the cached list and raises CacheMissError rather than fetching it
+    def get_ticker_universe(self, market='stocks', refresh=False, cached_only=False, limit=TICKER_UNIVERSE_LIMIT):
+        logger.info(f"Requesting the {market} ticker universe (refresh={refresh}, cached_only={cached_only}, limit={limit})")
+        params = {'market': market, 'active': 'true', 'sort': 'ticker', 'order': 'asc', 'limit': min(limit, MAX_PAGE_SIZE['tickers'])}
+
+        def fetch():
+            if cached_only:
+                raise CacheMissError(f"The {market} ticker list is not cached")
+            return self._paginate('/v3/reference/tickers', params, max_records=limit)
+
+        items = self._cached('ticker_universe', dict(params, max_records=limit), fetch, refresh=refresh and not cached_only)
+        return [TickerDetails.from_api(item) for item in items]
+
+    # Get reference details for a ticker
+    def get_ticker_details(self, ticker):
+        logger.info(f"Requesting company details for ticker: {ticker}")
+        payload = self._cached('ticker_details', {'ticker': ticker}, lambda: self._get(f"{self.base_url}/v3/reference/tickers/{ticker}"))
+        return TickerDetails.from_api(payload.get('results') or {'ticker': ticker})
+
+    # Get the tickers Polygon relates to a company (from news and returns), most related first
+    def get_related_companies(self, ticker):
+        logger.info(f"Requesting related companies for ticker: {ticker}")
+        payload = self._cached('related_companies', {'ticker': ticker}, lambda: self._get(f"{self.base_url}/v1/related-companies/{ticker}"))
+        return [item['ticker'] for item in payload.get('results') or [] if item.get('ticker')]
+
+    # Get financial reports for a ticker, newest first
+    def get_financials(self, ticker, limit=10, timeframe=None):
+        logger.info(f"Requesting financials data for {ticker} with limit {limit} and timeframe {timeframe}")
+        params = {'ticker': ticker, 'limit': min(limit, MAX_PAGE_SIZE['financials'])}
+        if timeframe:
+            params['timeframe'] = timeframe
+        items = self._cached('financials', dict(params, max_records=limit), lambda: self._paginate('/vX/reference/financials', params, max_records=limit))
+        return [FinancialReport.from_api(item) for item in items]
+
+    # Get stock splits, optionally for one ticker and filtered by execution date (gt, gte, lt, lte)
+    def get_stock_splits(self, ticker=None, limit=50, **date_filters):
+        logger.info(f"Requesting stock splits data for ticker: {ticker if ticker else 'All Tickers'} with limit: {limit}")
+        params = {'limit': min(limit, MAX_PAGE_SIZE['splits'])}
+        if ticker:
+            params['ticker'] = ticker
+        for key, value in date_filters.items():
+            if value:  # Only add the filter if the value is not None
+                params[f'execution_date.{key}'] = value
+        items = self._cached('splits', dict(params, max_records=limit), lambda: self._paginate('/v3/reference/splits', params, max_records=limit))
+        return [StockSplit.from_api(item) for item in items]
+
+    # Get dividends for a ticker
+    def get_dividends(self, ticker, limit=50):
+        logger.info(f"Requesting dividends data for ticker: {ticker} with limit: {limit}")
+        params = {'ticker': ticker, 'limit': min(limit, MAX_PAGE_SIZE['dividends'])}
+        items = self._cached('dividends', dict(params, max_records=limit), lambda: self._paginate('/v3/reference/dividends', params, max_records=limit))
+        return [Dividend.from_api(item) for item in items]
+
+    # Get the latest news, optionally for one ticker
+    def get_news(self, ticker=None, limit=5):
+        logger.info(f"Requesting news for ticker: {ticker if ticker else 'All Tickers'} with limit: {limit}")
+        params = {'limit': min(limit, MAX_PAGE_SIZE['news'])}
+        if ticker:
+            params['ticker'] = ticker
+        items = self._cached('news', dict(params, max_records=limit), lambda: self._paginate('/v2/reference/news', params, max_records=limit))
+        return [NewsArticle.from_api(item) for item in items]
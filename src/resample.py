@@ -0,0 +1,68 @@
+from datetime import time
+import pandas as pd
+
+# Local resampling of minute bars (DatetimeIndex in US/Eastern) into larger intervals, so one minute fetch serves every bar size
+
+# Bar intervals offered in the app, as pandas offset aliases; weeks start on Monday
+RESAMPLE_INTERVALS = {
+    '1 minute': '1min',
+    '5 minutes': '5min',
+    '15 minutes': '15min',
+    '30 minutes': '30min',
+    '1 hour': '60min',
+    '4 hours': '240min',
+    '1 day': '1D',
+    '1 week': 'W-MON',
+    '1 month': 'MS',
+}
+
+# Trading days of minute bars fetched by default
+RESAMPLE_DEFAULT_DAYS = 5
+
+# Intervals of a day or longer, whose bars follow the calendar days of the market
+DAY_RULES = {'1D', 'W-MON', 'MS'}
+
+# Regular trading hours in US/Eastern; the extended session is everything Polygon reports (4:00 to 20:00)
+REGULAR_OPEN = time(9, 30)
+REGULAR_CLOSE = time(16, 0)
+SESSIONS = ['regular', 'extended']
+
+# Keep only the bars of a trading session ('regular' or 'extended')
+def filter_session(df, session='regular'):
+    if session not in SESSIONS:
+        raise ValueError(f"Unknown session '{session}', expected one of {SESSIONS}")
+    if session == 'extended' or df.empty:
+        return df
+    times = df.index.time
+    return df[(times >= REGULAR_OPEN) & (times < REGULAR_CLOSE)]
+
+# Aggregate bars into a larger interval: first open, highest high, lowest low, last close, summed volume and trades, volume-weighted VWAP
+# day_timezone is where the market's days start when it is not US/Eastern ('UTC' for forex and crypto)
+def resample_bars(df, rule, day_timezone=None):
+    if df.empty:
+        return df
+    if day_timezone and rule in DAY_RULES:
+        # Bucket by the market's days and label each bar in that timezone, like daily bars from the API
+        return resample_bars(df.tz_convert(day_timezone), rule)
+    # Weekly bars are labelled with the Monday they start on, the same way Polygon labels them
+    options = dict(label='left', closed='left') if rule.startswith('W-') else {}
+    resampler = df.resample(rule, **options)
+    bars = pd.DataFrame({
+        'Open': resampler['Open'].first(),
+        'High': resampler['High'].max(),
+        'Low': resampler['Low'].min(),
+        'Close': resampler['Close'].last(),
+    })
+    # Index bars have no volume
+    if 'Volume' in df.columns:
+        bars['Volume'] = resampler['Volume'].sum()
+        if 'VWAP' in df.columns:
+            bars['VWAP'] = (df['VWAP'] * df['Volume']).resample(rule, **options).sum() / bars['Volume']
+    if 'Transactions' in df.columns:
+        bars['Transactions'] = resampler['Transactions'].sum(min_count=1).astype('Int64')
+    # Intervals without any trade (nights, weekends, holidays) produce no bar
+    bars = bars.dropna(subset=['Open'])
+    if 'Volume' in bars.columns:
+        bars['Volume'] = bars['Volume'].astype(df['Volume'].dtype)  # Whole shares stay integers, fractions of a coin stay floats
+    bars.index.name = df.index.name
+    return bars
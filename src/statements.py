@@ -0,0 +1,46 @@
+import pandas as pd
+
+# Traditional statement tables built from /vX/reference/financials reports: line items as rows in filing order, periods as columns
+
+# Statements of a report and their titles, in the order they are shown
+STATEMENTS = {
+    'income_statement': 'Income Statement',
+    'balance_sheet': 'Balance Sheet',
+    'cash_flow_statement': 'Cash Flow Statement',
+    'comprehensive_income': 'Comprehensive Income',
+}
+
+# Totals shown while a statement is collapsed; expanding it lists every line item of the filing
+SUMMARY_ITEMS = {
+    'income_statement': ['revenues', 'gross_profit', 'operating_income_loss', 'net_income_loss', 'diluted_earnings_per_share'],
+    'balance_sheet': ['assets', 'current_assets', 'liabilities', 'current_liabilities', 'equity'],
+    'cash_flow_statement': ['net_cash_flow_from_operating_activities', 'net_cash_flow_from_investing_activities', 'net_cash_flow_from_financing_activities', 'net_cash_flow'],
+    'comprehensive_income': ['comprehensive_income_loss'],
+}
+
+# Period choices and the timeframe parameter of the financials endpoint
+TIMEFRAMES = {'Quarterly': 'quarterly', 'Annual': 'annual', 'TTM': 'ttm'}
+
+
+# Table of one statement indexed by line item label, with the unit followed by one column per period (newest first)
+# When several reports cover the same period (amended filings), the first one in the API's order is kept
+def statement_table(reports, statement, detailed=True):
+    rows = {}
+    periods = []
+    for report in sorted(reports, key=lambda report: report.end_date or '', reverse=True):
+        period = report.period_label
+        if period in periods:
+            continue
+        periods.append(period)
+        for key, point in report.statement(statement):
+            row = rows.setdefault(key, {'Line Item': point.label or key.replace('_', ' ').title(), 'Unit': point.unit, 'Order': point.order})
+            row[period] = point.value
+    if not detailed:
+        rows = {key: row for key, row in rows.items() if key in SUMMARY_ITEMS.get(statement, [])}
+    if not rows:
+        return pd.DataFrame()
+    # Line items missing from the newest filing keep their place through the order field
+    df = pd.DataFrame(list(rows.values()), columns=['Line Item', 'Unit', 'Order'] + periods)
+    df = df.sort_values('Order', kind='stable', na_position='last').set_index('Line Item')
+    df[periods] = df[periods].astype('float64')
+    return df.drop(columns='Order')
@@ -0,0 +1,251 @@
+import json
+import threading
+from collections import OrderedDict
+import pandas as pd
+from websockets.exceptions import WebSocketException
+from websockets.sync.client import connect
+import config.api_config as api_config
+import config.log_config
+from markets import get_market, currency_pair
+from models import Aggregate, StreamTrade, StreamQuote
+
+# Initialize the logger
+logger = config.log_config.setup_logging()
+
+# Event names of each channel of the WebSocket feed per market (forex has no trades, indices only aggregates)
+STREAM_CHANNELS = {
+    'stocks': {'trades': 'T', 'quotes': 'Q', 'second': 'A', 'minute': 'AM'},
+    'fx': {'quotes': 'C', 'second': 'CAS', 'minute': 'CA'},
+    'crypto': {'trades': 'XT', 'quotes': 'XQ', 'second': 'XAS', 'minute': 'XA'},
+    'indices': {'second': 'A', 'minute': 'AM'},
+}
+
+# Bar length in seconds of the aggregate channels
+AGGREGATE_SECONDS = {'second': 1, 'minute': 60}
+
+
+# Raised when the feed rejects the API key; the stream gives up instead of reconnecting
+class StreamAuthError(Exception):
+    pass
+
+
+# Symbol of a ticker in subscriptions: 'AAPL', 'I:SPX', 'BTC-USD' for X:BTCUSD and 'EUR/USD' for C:EURUSD
+def stream_symbol(ticker, market):
+    if market == 'crypto':
+        return '-'.join(currency_pair(ticker))
+    if market == 'fx':
+        return '/'.join(currency_pair(ticker))
+    return ticker
+
+# Ticker of an event: stocks and indices carry 'sym', crypto 'pair' (BTC-USD), forex aggregates 'pair' and forex quotes 'p' (EUR/USD)
+# None when the event has no symbol
+def event_ticker(event, market):
+    symbol = event.get('sym') or event.get('pair') or event.get('p')
+    if not isinstance(symbol, str):
+        return None
+    prefix = get_market(market).ticker_prefix
+    if not prefix or symbol.startswith(prefix):
+        return symbol
+    return prefix + symbol.replace('-', '').replace('/', '')
+
+
+# Rolling window of the latest bars of each ticker, written by the stream thread and read by the chart
+# Aggregate events replace the bar with the same start time, as Polygon resends bars updated by late trades;
+# with from_trades, trades are rolled up into bars of `interval` seconds instead
+class BarBuffer:
+    def __init__(self, interval=60, max_bars=500, from_trades=False):
+        self.interval = interval
+        self.max_bars = max_bars
+        self.from_trades = from_trades
+        self.bars = {}
+        self.last_trades = {}
+        self.last_quotes = {}
+        self.version = 0  # Counts updates so readers can tell whether anything changed
+        self.lock = threading.Lock()
+
+    # Store a bar in start time order and drop the oldest bars beyond max_bars
+    def _put(self, bar):
+        bars = self.bars.setdefault(bar.ticker, OrderedDict())
+        out_of_order = bars and bar.timestamp < next(reversed(bars))
+        bars[bar.timestamp] = bar
+        if out_of_order:
+            self.bars[bar.ticker] = bars = OrderedDict(sorted(bars.items()))
+        while len(bars) > self.max_bars:
+            bars.popitem(last=False)
+        self.version += 1
+
+    def add_aggregate(self, bar):
+        with self.lock:
+            self._put(bar)
+
+    def add_trade(self, trade):
+        with self.lock:
+            self.last_trades[trade.ticker] = trade
+            if not self.from_trades:
+                self.version += 1
+                return
+            start = trade.timestamp // (self.interval * 1000) * self.interval * 1000
+            bar = self.bars.get(trade.ticker, {}).get(start)
+            if bar is None:
+                self._put(Aggregate(start, trade.price, trade.price, trade.price, trade.price, volume=trade.size, vwap=trade.price, transactions=1, ticker=trade.ticker))
+                return
+            volume = bar.volume + trade.size
+            if volume:
+                bar.vwap = (bar.vwap * bar.volume + trade.price * trade.size) / volume
+            bar.high, bar.low, bar.close = max(bar.high, trade.price), min(bar.low, trade.price), trade.price
+            bar.volume, bar.transactions = volume, bar.transactions + 1
+            self.version += 1
+
+    def add_quote(self, quote):
+        with self.lock:
+            self.last_quotes[quote.ticker] = quote
+            self.version += 1
+
+    # Bars of a ticker indexed by start time in US/Eastern, with the columns of get_historical_data_as_df
+    def to_frame(self, ticker):
+        with self.lock:
+            bars = [(bar.timestamp, bar.open, bar.high, bar.low, bar.close, bar.volume, bar.vwap, bar.transactions) for bar in self.bars.get(ticker, {}).values()]
+        df = pd.DataFrame(bars, columns=['Date', 'Open', 'High', 'Low', 'Close', 'Volume', 'VWAP', 'Transactions'])
+        df['Date'] = pd.to_datetime(df['Date'], unit='ms', utc=True).dt.tz_convert('US/Eastern')
+        df = df.set_index('Date').astype({'Open': 'float64', 'High': 'float64', 'Low': 'float64', 'Close': 'float64', 'Volume': 'float64', 'VWAP': 'float64'})
+        df['Transactions'] = df['Transactions'].astype('Int64')
+        # Index aggregates have no volume
+        return df.drop(columns=['Volume', 'VWAP']) if df['Volume'].isna().all() and not df.empty else df
+
+
+# Connection to Polygon's WebSocket feed of one market on a background thread, feeding a BarBuffer
+# channels are keys of STREAM_CHANNELS ('trades', 'quotes', 'second', 'minute'); without an aggregate channel bars are built from trades
+# The connection is re-established with exponential backoff when it drops; a rejected API key or an unexpected error stops
+# the stream with the 'error' status, while malformed messages and events are logged and skipped
+class PolygonStream:
+    def __init__(self, api_key, tickers, channels=('minute',), market='stocks', url=None, buffer=None, interval=60, max_bars=500):
+        self.market = get_market(market)
+        unsupported = [channel for channel in channels if channel not in STREAM_CHANNELS[self.market.name]]
+        if unsupported:
+            raise ValueError(f"There is no {', '.join(unsupported)} stream for {self.market.label.lower()}")
+        aggregates = [channel for channel in channels if channel in AGGREGATE_SECONDS]
+        if not aggregates and 'trades' not in channels:
+            raise ValueError("Subscribe to trades or to an aggregate channel to build bars")
+        self.api_key = api_key
+        self.tickers = list(tickers)
+        self.channels = list(channels)
+        self.url = url or f"{api_config.STREAM_URL.rstrip('/')}/{self.market.stream_cluster}"
+        if buffer is None:
+            interval = AGGREGATE_SECONDS[aggregates[0]] if aggregates else interval
+            buffer = BarBuffer(interval, max_bars, from_trades=not aggregates)
+        self.buffer = buffer
+        self.status = 'stopped'
+        self.error = None
+        self.events = 0
+        self._events = {STREAM_CHANNELS[self.market.name][channel]: channel for channel in self.channels}
+        self._stop = threading.Event()
+        self._thread = None
+        self._connection = None
+
+    # Subscription parameter, e.g. 'AM.AAPL,T.AAPL'
+    def subscription(self):
+        events = STREAM_CHANNELS[self.market.name]
+        return ','.join(f"{events[channel]}.{stream_symbol(ticker, self.market.name)}" for ticker in self.tickers for channel in self.channels)
+
+    def start(self):
+        self._stop.clear()
+        self.status = 'connecting'
+        self._thread = threading.Thread(target=self._run, name=f"polygon-stream-{self.market.name}", daemon=True)
+        self._thread.start()
+        return self
+
+    def stop(self, timeout=5):
+        self._stop.set()
+        connection = self._connection
+        if connection is not None:
+            connection.close()
+        if self._thread is not None:
+            self._thread.join(timeout)
+        self.status = 'stopped'
+
+    @property
+    def running(self):
+        return self._thread is not None and self._thread.is_alive()
+
+    def _run(self):
+        attempt = 0
+        while not self._stop.is_set():
+            try:
+                with connect(self.url, open_timeout=api_config.CONNECT_TIMEOUT) as connection:
+                    self._connection = connection
+                    self._authenticate(connection)
+                    connection.send(json.dumps({'action': 'subscribe', 'params': self.subscription()}))
+                    logger.info(f"Subscribed to {self.subscription()} on {self.url}")
+                    self.status = 'streaming'
+                    attempt = 0
+                    for message in connection:
+                        self.handle_message(message)
+            except StreamAuthError as e:
+                logger.error(f"Stream authentication failed on {self.url}: {e}")
+                self.status, self.error = 'error', str(e)
+                return
+            except (OSError, TimeoutError, WebSocketException) as e:
+                logger.warning(f"Stream connection to {self.url} lost: {e}")
+                self.error = str(e)
+            except Exception as e:
+                logger.exception(f"Stream from {self.url} failed")
+                self.status, self.error = 'error', str(e)
+                return
+            finally:
+                self._connection = None
+            if self._stop.is_set():
+                break
+            attempt += 1
+            delay = min(api_config.BACKOFF_MAX, api_config.BACKOFF_BASE * 2 ** (attempt - 1))
+            self.status = 'reconnecting'
+            logger.info(f"Reconnecting to {self.url} in {delay:.1f}s (attempt {attempt})")
+            self._stop.wait(delay)
+
+    # Wait for the connected status, send the API key and wait for the verdict
+    def _authenticate(self, connection):
+        connection.send(json.dumps({'action': 'auth', 'params': self.api_key}))
+        while True:
+            for event in json.loads(connection.recv(timeout=api_config.READ_TIMEOUT)):
+                if event.get('ev') != 'status':
+                    continue
+                if event.get('status') == 'auth_success':
+                    return
+                if event.get('status') == 'auth_failed':
+                    raise StreamAuthError(event.get('message', 'authentication failed'))
+
+    # Dispatch the events of one message (a JSON array) to the buffer; malformed messages and events are skipped
+    def handle_message(self, message):
+        try:
+            events = json.loads(message)
+        except ValueError as e:
+            logger.warning(f"Skipping malformed stream message {message!r:.200}: {e}")
+            return
+        if not isinstance(events, list):
+            logger.warning(f"Skipping stream message that is not an array of events: {message!r:.200}")
+            return
+        for event in events:
+            if not isinstance(event, dict):
+                logger.warning(f"Skipping malformed stream event {event!r:.200}")
+                continue
+            channel = self._events.get(event.get('ev'))
+            if channel is None:
+                if event.get('ev') == 'status':
+                    logger.info(f"Stream status: {event.get('status')} {event.get('message', '')}")
+                continue
+            try:
+                self.handle_event(event, channel)
+            except (KeyError, TypeError, ValueError) as e:
+                logger.warning(f"Skipping malformed stream event {event!r:.200}: {e!r}")
+
+    # Add one trade, quote or aggregate event to the buffer
+    def handle_event(self, event, channel):
+        ticker = event_ticker(event, self.market.name)
+        if ticker is None:
+            raise ValueError("the event has no symbol")
+        if channel == 'trades':
+            self.buffer.add_trade(StreamTrade.from_stream(event, ticker))
+        elif channel == 'quotes':
+            self.buffer.add_quote(StreamQuote.from_stream(event, ticker))
+        else:
+            self.buffer.add_aggregate(Aggregate.from_stream(event, ticker))
+        self.events += 1
@@ -0,0 +1,80 @@
+# Names of the exchange and trade condition codes used by Polygon's US stock trades and quotes
+
+# Exchange IDs as reported in trades (exchange) and quotes (bid_exchange, ask_exchange)
+EXCHANGES = {
+    1: 'NYSE American',
+    2: 'Nasdaq BX',
+    3: 'NYSE National',
+    4: 'FINRA ADF',
+    5: 'Unlisted Trading Privileges',
+    6: 'ISE Stocks',
+    7: 'Cboe EDGA',
+    8: 'Cboe EDGX',
+    9: 'NYSE Chicago',
+    10: 'NYSE',
+    11: 'NYSE Arca',
+    12: 'Nasdaq',
+    13: 'Consolidated Tape Association',
+    14: 'Long-Term Stock Exchange',
+    15: 'IEX',
+    16: 'Cboe Stock Exchange',
+    17: 'Nasdaq PSX',
+    18: 'Cboe BYX',
+    19: 'Cboe BZX',
+    20: 'MIAX Pearl',
+    21: 'Members Exchange',
+    62: 'OTC Equity Security',
+}
+
+# Trade condition IDs as reported in trades (conditions)
+TRADE_CONDITIONS = {
+    0: 'Regular Trade',
+    1: 'Acquisition',
+    2: 'Average Price Trade',
+    3: 'Automatic Execution',
+    4: 'Bunched Trade',
+    5: 'Bunched Sold Trade',
+    6: 'CAP Election',
+    7: 'Cash Sale',
+    8: 'Closing Prints',
+    9: 'Cross Trade',
+    10: 'Derivatively Priced',
+    11: 'Distribution',
+    12: 'Form T',
+    13: 'Extended Trading Hours (Sold Out of Sequence)',
+    14: 'Intermarket Sweep',
+    15: 'Market Center Official Close',
+    16: 'Market Center Official Open',
+    17: 'Market Center Opening Trade',
+    18: 'Market Center Reopening Trade',
+    19: 'Market Center Closing Trade',
+    20: 'Next Day',
+    21: 'Price Variation Trade',
+    22: 'Prior Reference Price',
+    23: 'Rule 155 Trade (AMEX)',
+    24: 'Rule 127 (NYSE Only)',
+    25: 'Opening Prints',
+    27: 'Stopped Stock (Regular Trade)',
+    28: 'Re-Opening Prints',
+    29: 'Seller',
+    30: 'Sold Last',
+    33: 'Sold (Out of Sequence)',
+    34: 'Split Trade',
+    35: 'Stock Option',
+    36: 'Yellow Flag Regular Trade',
+    37: 'Odd Lot Trade',
+    38: 'Corrected Consolidated Close',
+    41: 'Trade Thru Exempt',
+    52: 'Contingent Trade',
+    53: 'Qualified Contingent Trade',
+}
+
+# Name of an exchange ID, falling back to the raw code
+def exchange_name(code, exchanges=EXCHANGES):
+    if code is None:
+        return None
+    return exchanges.get(code, f"Exchange {code}")
+
+# Comma separated names of trade condition IDs, falling back to the raw codes
+def condition_names(codes, conditions=TRADE_CONDITIONS):
+    return ', '.join(conditions.get(code, f"Condition {code}") for code in codes or [])
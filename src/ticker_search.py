@@ -0,0 +1,82 @@
+import streamlit as st
+from polygon_api import get_ticker_universe_as_df
+from polygon_client import TICKER_UNIVERSE_LIMIT
+
+# Ticker pickers shared by every page: searchable select boxes over the cached ticker list, falling back to free text
+# while the list loads in the background or when it cannot be loaded (e.g. offline without a cached list)
+# The list is capped at TICKER_UNIVERSE_LIMIT tickers; past that, tickers can still be typed
+
+# Ticker types offered in the search filters
+TICKER_TYPES = {
+    'CS': 'Common Stock',
+    'ETF': 'Exchange Traded Fund',
+    'ADRC': 'American Depository Receipt',
+    'PFD': 'Preferred Stock',
+    'FUND': 'Fund',
+    'UNIT': 'Unit',
+    'RIGHT': 'Rights',
+    'WARRANT': 'Warrant',
+    'INDEX': 'Index',
+}
+
+# Markets offered in the search filters
+SEARCH_MARKETS = ['stocks', 'otc', 'crypto', 'fx', 'indices']
+
+# Company names by ticker, for the picker labels (empty until the list is loaded)
+def ticker_names(api_key, market='stocks'):
+    universe = get_ticker_universe_as_df(api_key, market)
+    return {} if universe.empty else universe['Name'].fillna('').to_dict()
+
+# Whether a ticker list stops at the cap, leaving out the tickers after it
+def truncated(names):
+    return len(names) >= TICKER_UNIVERSE_LIMIT
+
+# Label of a ticker in the pickers, e.g. 'AAPL - Apple Inc.'
+def ticker_label(ticker, names):
+    name = names.get(ticker)
+    return f"{ticker} - {name}" if name else ticker
+
+# Widget key of a picker in a market, so switching markets does not carry tickers over to pickers of another market
+def market_key(key, market):
+    return f"{key}_{market}" if key and market != 'stocks' else key
+
+# Pick one ticker; with optional=True an empty choice ('') stands for all tickers
+def ticker_select(label, api_key, default='AAPL', key=None, optional=False, market='stocks'):
+    return select_ticker(label, ticker_names(api_key, market), default, market_key(key, market), optional)
+
+# Pick the benchmark of a comparison among the tickers of a market and the indices (e.g. I:SPX)
+def benchmark_select(label, api_key, default, key=None, market='stocks'):
+    names = ticker_names(api_key, market)
+    if names:
+        names.update(ticker_names(api_key, 'indices'))
+    return select_ticker(label, names, default, market_key(key, market))
+
+# Pick one ticker from a {ticker: name} list, as free text when the list is empty
+def select_ticker(label, names, default, key=None, optional=False):
+    if not names:
+        return st.text_input(label, default or '', key=key).strip().upper()
+    options = list(names)
+    if default and default not in names:
+        options.insert(0, default)
+    if optional:
+        options.insert(0, '')
+    index = options.index(default) if default in options else 0
+    ticker = st.selectbox(label, options, index=index, key=key, format_func=lambda ticker: ticker_label(ticker, names) if ticker else 'All tickers')
+    if truncated(names):
+        typed = st.text_input(f"{label}: other ticker", '', key=f"{key}_other" if key else None).strip().upper()
+        ticker = typed or ticker
+    return ticker
+
+# Pick several tickers
+def ticker_multiselect(label, api_key, default, key=None, market='stocks'):
+    key = market_key(key, market)
+    names = ticker_names(api_key, market)
+    if not names:
+        text = st.text_input(f"{label} (comma separated)", ', '.join(default), key=key)
+        return [symbol.strip().upper() for symbol in text.split(',') if symbol.strip()]
+    options = list(names) + [ticker for ticker in default if ticker not in names]
+    tickers = st.multiselect(label, options, default=default, key=key, format_func=lambda ticker: ticker_label(ticker, names))
+    if truncated(names):
+        text = st.text_input(f"{label}: other tickers (comma separated)", '', key=f"{key}_other" if key else None)
+        tickers += [symbol.strip().upper() for symbol in text.split(',') if symbol.strip() and symbol.strip().upper() not in tickers]
+    return tickers
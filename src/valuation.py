@@ -0,0 +1,158 @@
+import pandas as pd
+from financial_metrics import line_items, item, first_item, safe_divide, total_debt, free_cash_flow, financial_metrics
+
+# Valuation of a company from its financial reports and daily closes: price multiples over time and a discounted cash flow model
+# Reports should cover a year each (annual or TTM); prices are not split-adjusted so they match the per-share figures
+# and share counts of the filings at the time
+
+# Line items only some filers report, tried in order
+CASH_ITEMS = ('cash', 'cash_and_cash_equivalents')
+DEPRECIATION_ITEMS = ('depreciation_and_amortization', 'depreciation_amortization_and_accretion')
+SHARE_ITEMS = ('diluted_average_shares', 'basic_average_shares')
+
+MULTIPLE_COLUMNS = ['P/E', 'P/S', 'P/B', 'EV/EBITDA', 'FCF Yield (%)', 'Dividend Yield (%)']
+
+# A report is priced at the last close on or before its end date, up to this many days earlier
+PRICE_TOLERANCE_DAYS = 7
+
+# Special dividends are one-offs and left out of the trailing dividend
+SPECIAL_DIVIDEND_TYPES = {'SC'}
+
+# Assumptions used when the filings do not provide them
+DEFAULT_GROWTH = 0.05
+DEFAULT_DISCOUNT_RATE = 0.09
+DEFAULT_TERMINAL_GROWTH = 0.025
+
+# Names of the DCF inputs taken from the filings, and what stands in for them when the filings lack them
+DCF_INPUT_LABELS = {'growth': 'revenue growth', 'fcf_margin': 'free cash flow margin', 'net_debt': 'net debt'}
+DCF_FALLBACK_LABELS = {'growth': f"{DEFAULT_GROWTH:.0%} a year", 'fcf_margin': 'the net margin', 'net_debt': 'zero'}
+
+
+# Diluted share count of each report, implied from net income over diluted EPS when the filing leaves it out
+def shares_outstanding(items):
+    implied = safe_divide(item(items, 'net_income_loss'), item(items, 'diluted_earnings_per_share'))
+    return first_item(items, SHARE_ITEMS).fillna(implied)
+
+# Debt less cash (NaN unless the filing reports both)
+def net_debt(items):
+    return total_debt(items) - first_item(items, CASH_ITEMS)
+
+# Close on or before each date (NaN when the last close is older than PRICE_TOLERANCE_DAYS)
+def price_on(closes, dates):
+    closes = closes.sort_index()
+    index = closes.index.tz_localize(None).normalize() if closes.index.tz is not None else closes.index.normalize()
+    closes = pd.Series(closes.to_numpy(), index=index)
+    closes = closes[~closes.index.duplicated(keep='last')]
+    if closes.empty:
+        return pd.Series(float('nan'), index=dates.index)
+    prices = closes.reindex(pd.DatetimeIndex(dates), method='ffill', tolerance=pd.Timedelta(days=PRICE_TOLERANCE_DAYS))
+    return pd.Series(prices.to_numpy(), index=dates.index)
+
+# Regular cash dividends per share with an ex-dividend date in the year up to each date
+def trailing_dividends(dividends, dates):
+    paid = [(pd.Timestamp(dividend.ex_dividend_date), dividend.cash_amount) for dividend in dividends
+            if dividend.ex_dividend_date and dividend.dividend_type not in SPECIAL_DIVIDEND_TYPES]
+    totals = [sum(amount for ex_date, amount in paid if date - pd.DateOffset(years=1) < ex_date <= date) if pd.notna(date) else float('nan')
+              for date in dates]
+    return pd.Series(totals, index=dates.index, dtype='float64')
+
+# Multiples of each report at the given prices and trailing dividends per share
+# P/E and EV/EBITDA are left out for losses, where they are not meaningful
+def compute_multiples(items, price, dividends_per_share):
+    eps = item(items, 'diluted_earnings_per_share')
+    market_cap = price * shares_outstanding(items)
+    ebitda = item(items, 'operating_income_loss') + first_item(items, DEPRECIATION_ITEMS)
+    enterprise_value = market_cap + net_debt(items)
+    return pd.DataFrame({
+        'Period': items['Period'],
+        'Price': price,
+        'Market Cap': market_cap,
+        'P/E': safe_divide(price, eps.where(eps > 0)),
+        'P/S': safe_divide(market_cap, item(items, 'revenues')),
+        'P/B': safe_divide(market_cap, item(items, 'equity')),
+        'EV/EBITDA': safe_divide(enterprise_value, ebitda.where(ebitda > 0)),
+        'FCF Yield (%)': safe_divide(free_cash_flow(items), market_cap) * 100,
+        'Dividend Yield (%)': safe_divide(dividends_per_share, price) * 100,
+    }, index=items.index)
+
+# Multiples of each report priced at its end date, indexed by end date, oldest first
+def valuation_multiples(reports, closes, dividends=()):
+    items = line_items(reports).sort_values('End Date')
+    price = price_on(closes, items['End Date'])
+    multiples = compute_multiples(items, price, trailing_dividends(dividends, items['End Date']))
+    return multiples.set_index(items['End Date'])
+
+# Multiples of the latest report at the latest close
+def current_multiples(reports, closes, dividends=()):
+    items = line_items(reports).sort_values('End Date').tail(1)
+    closes = closes.sort_index()
+    as_of = closes.index[-1].tz_localize(None) if closes.index.tz is not None else closes.index[-1]
+    dates = pd.Series(as_of.normalize(), index=items.index)
+    price = pd.Series(closes.iloc[-1], index=items.index)
+    return compute_multiples(items, price, trailing_dividends(dividends, dates)).iloc[0]
+
+
+# Starting point of the DCF from the latest report: revenue, growth, free cash flow margin, net debt and shares
+# Inputs the filings lack fall back to defaults (net margin stands in for the free cash flow margin) and are listed in 'estimated'
+def dcf_defaults(reports):
+    items = line_items(reports).sort_values('End Date')
+    metrics = financial_metrics(reports).loc[items.index]
+    latest, latest_metrics = items.iloc[[-1]], metrics.iloc[-1]
+    revenue = item(latest, 'revenues').iloc[0]
+    defaults = {
+        'revenue': revenue,
+        'growth': latest_metrics['Revenue Growth YoY (%)'] / 100,
+        'fcf_margin': latest_metrics['Free Cash Flow'] / revenue if revenue else float('nan'),
+        'net_debt': net_debt(latest).iloc[0],
+        'shares': shares_outstanding(latest).iloc[0],
+        'estimated': [],
+    }
+    fallbacks = {'growth': DEFAULT_GROWTH, 'fcf_margin': latest_metrics['Net Margin (%)'] / 100, 'net_debt': 0.0}
+    for name, fallback in fallbacks.items():
+        if pd.isna(defaults[name]):
+            defaults[name] = fallback
+            defaults['estimated'].append(name)
+    return defaults
+
+# Project free cash flow for `years` (revenue growing at `growth` a year, converted at `fcf_margin`), discount it at
+# `discount_rate` and add a terminal value growing at `terminal_growth` forever after the last year
+# Returns the yearly projection and a summary down to the value per share
+def discounted_cash_flow(revenue, growth, fcf_margin, discount_rate, terminal_growth, years=5, net_debt=0.0, shares=None):
+    if discount_rate <= terminal_growth:
+        raise ValueError("The discount rate must be higher than the terminal growth rate")
+    year = pd.RangeIndex(1, years + 1, name='Year')
+    revenues = pd.Series([revenue * (1 + growth) ** n for n in year], index=year)
+    cash_flows = revenues * fcf_margin
+    discount_factors = pd.Series([1 / (1 + discount_rate) ** n for n in year], index=year)
+    projection = pd.DataFrame({
+        'Revenue': revenues,
+        'Free Cash Flow': cash_flows,
+        'Discount Factor': discount_factors,
+        'Present Value': cash_flows * discount_factors,
+    })
+    terminal_value = cash_flows.iloc[-1] * (1 + terminal_growth) / (discount_rate - terminal_growth)
+    terminal_present_value = terminal_value * discount_factors.iloc[-1]
+    enterprise_value = projection['Present Value'].sum() + terminal_present_value
+    equity_value = enterprise_value - net_debt
+    summary = {
+        'Present Value of Cash Flows': projection['Present Value'].sum(),
+        'Present Value of Terminal Value': terminal_present_value,
+        'Enterprise Value': enterprise_value,
+        'Equity Value': equity_value,
+        'Value per Share': equity_value / shares if shares else float('nan'),
+    }
+    return projection, summary
+
+# Value per share for each discount rate (rows) and terminal growth rate (columns); NaN where the rate does not exceed the growth
+def dcf_sensitivity(revenue, growth, fcf_margin, discount_rates, terminal_growths, years=5, net_debt=0.0, shares=None):
+    table = pd.DataFrame(index=pd.Index(discount_rates, name='Discount Rate'), columns=pd.Index(terminal_growths, name='Terminal Growth'), dtype='float64')
+    for discount_rate in discount_rates:
+        for terminal_growth in terminal_growths:
+            if discount_rate > terminal_growth:
+                _, summary = discounted_cash_flow(revenue, growth, fcf_margin, discount_rate, terminal_growth, years, net_debt, shares)
+                table.loc[discount_rate, terminal_growth] = summary['Value per Share']
+    return table
+
+# Rates around a central one, e.g. [0.08, 0.085, 0.09, 0.095, 0.10] for 0.09 with a 0.005 step
+def rate_range(center, step, count=5):
+    return [round(center + step * (n - count // 2), 6) for n in range(count)]
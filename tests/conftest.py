@@ -0,0 +1,59 @@
+import atexit
+import os
+import tempfile
+import pytest
+from mock_polygon import MockPolygonServer
+from replay_polygon import ReplayServer
+
+# Start the mock servers and point the app at them before any application module reads its configuration
+mock_server = MockPolygonServer().start()
+atexit.register(mock_server.stop)
+replay_server = ReplayServer().start()
+atexit.register(replay_server.stop)
+os.environ['POLYGON_BASE_URL'] = mock_server.base_url
+os.environ['POLYGON_STREAM_URL'] = replay_server.base_url
+os.environ['POLYGON_CACHE_PATH'] = os.path.join(tempfile.mkdtemp(), 'polygon.sqlite')
+os.environ['POLYGON_RATE_LIMIT_REQUESTS'] = '0'
+os.environ['POLYGON_MAX_RETRIES'] = '0'
+
+from cache import ResponseCache
+from http_client import HttpSession
+from polygon_client import PolygonClient
+
+
+@pytest.fixture
+def mock_polygon():
+    mock_server.reset()
+    yield mock_server
+    mock_server.reset()
+
+
+@pytest.fixture
+def replay_polygon():
+    return replay_server
+
+
+@pytest.fixture
+def client(mock_polygon):
+    return PolygonClient('test-key', base_url=mock_polygon.base_url, session=HttpSession(max_retries=0))
+
+
+@pytest.fixture
+def cache(tmp_path):
+    return ResponseCache(str(tmp_path / 'polygon.sqlite'))
+
+
+@pytest.fixture
+def cached_client(mock_polygon, cache):
+    return PolygonClient('test-key', base_url=mock_polygon.base_url, session=HttpSession(max_retries=0), cache=cache)
+
+
+# Streamlit caches and the app's response cache outlive a single test, so clear them to keep tests independent
+@pytest.fixture(autouse=True)
+def clear_app_caches():
+    import streamlit as st
+    st.cache_data.clear()
+    st.cache_resource.clear()
+    if os.path.exists(os.environ['POLYGON_CACHE_PATH']):
+        os.remove(os.environ['POLYGON_CACHE_PATH'])
+    yield
@@ -0,0 +1,407 @@
+import argparse
+import json
+import os
+import re
+import threading
+from datetime import date, datetime, timedelta
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import urlencode, urlparse, parse_qs
+from zoneinfo import ZoneInfo
+
+# Recorded Polygon responses served by the mock
+FIXTURES_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'fixtures')
+
+MARKET_TIMEZONE = ZoneInfo('America/New_York')
+
+# Snapshot market names of the stock, forex and crypto snapshot endpoints
+SNAPSHOT_MARKETS = {'stocks': 'stocks', 'forex': 'fx', 'crypto': 'crypto'}
+
+
+# Load a recorded response from tests/fixtures (None when it does not exist); the ':' of forex and crypto tickers becomes '_'
+def load_fixture(name):
+    path = os.path.join(FIXTURES_DIR, f"{name.replace(':', '_')}.json")
+    if not os.path.exists(path):
+        return None
+    with open(path, encoding='utf-8') as f:
+        return json.load(f)
+
+
+# Unix milliseconds at midnight of a 'YYYY-MM-DD' date, US/Eastern for stocks and UTC for forex and crypto
+def day_start_ms(value, timezone=MARKET_TIMEZONE):
+    day = date.fromisoformat(value)
+    return int(datetime(day.year, day.month, day.day, tzinfo=timezone).timestamp() * 1000)
+
+
+# Moving averages of a price list, None until the window is full
+def simple_average(values, window):
+    return [sum(values[i - window + 1:i + 1]) / window if i >= window - 1 else None for i in range(len(values))]
+
+
+def exponential_average(values, alpha, window=1):
+    averages, average = [], None
+    for count, value in enumerate(values, start=1):
+        # Seeded with the first value
+        average = value if average is None else alpha * value + (1 - alpha) * average
+        averages.append(average if count >= window else None)
+    return averages
+
+
+# Indicator values of a price list like Polygon's /v1/indicators, computed independently of src/indicators.py
+def indicator_values(indicator, prices, query):
+    if indicator == 'sma':
+        return [{'value': value} if value is not None else None for value in simple_average(prices, int(query.get('window', 50)))]
+    if indicator == 'ema':
+        window = int(query.get('window', 50))
+        return [{'value': value} if value is not None else None for value in exponential_average(prices, 2 / (window + 1), window)]
+    if indicator == 'rsi':
+        window = int(query.get('window', 14))
+        changes = [current - previous for previous, current in zip(prices, prices[1:])]
+        gains = exponential_average([max(change, 0) for change in changes], 1 / window, window)
+        losses = exponential_average([max(-change, 0) for change in changes], 1 / window, window)
+        values = [None if gain is None else {'value': 100.0 if loss == 0 else 100 - 100 / (1 + gain / loss)} for gain, loss in zip(gains, losses)]
+        return [None] + values
+    short_window, long_window = int(query.get('short_window', 12)), int(query.get('long_window', 26))
+    macd = [fast - slow for fast, slow in zip(exponential_average(prices, 2 / (short_window + 1)), exponential_average(prices, 2 / (long_window + 1)))]
+    signal = exponential_average(macd, 2 / (int(query.get('signal_window', 9)) + 1))
+    return [{'value': value, 'signal': line, 'histogram': value - line} if i >= long_window - 1 else None for i, (value, line) in enumerate(zip(macd, signal))]
+
+
+# Name of the snapshot fixture of a market: snapshot_tickers for stocks, snapshot_fx_tickers and snapshot_crypto_tickers otherwise
+def snapshot_fixture(market):
+    market = SNAPSHOT_MARKETS[market]
+    return load_fixture('snapshot_tickers' if market == 'stocks' else f"snapshot_{market}_tickers")['tickers']
+
+
+# Local stand-in for api.polygon.io that serves tests/fixtures with Polygon's pagination and error behaviour
+class MockPolygonServer:
+    def __init__(self, host='127.0.0.1', port=0):
+        self.overrides = {}
+        self.requests = []
+        self.lock = threading.Lock()
+        self.httpd = ThreadingHTTPServer((host, port), self._make_handler())
+        self.thread = None
+        # Routes are matched in order against the request path
+        self.routes = [
+            (re.compile(r'^/v2/aggs/ticker/(?P<ticker>[^/]+)/range/(?P<multiplier>\d+)/(?P<timespan>\w+)/(?P<from_date>[^/]+)/(?P<to_date>[^/]+)$'), self.handle_aggs),
+            (re.compile(r'^/v2/aggs/ticker/(?P<ticker>[^/]+)/prev$'), self.handle_previous_close),
+            (re.compile(r'^/v1/indicators/(?P<indicator>sma|ema|rsi|macd)/(?P<ticker>[^/]+)$'), self.handle_indicator),
+            (re.compile(r'^/v1/open-close/(?P<ticker>[^/]+)/(?P<day>[^/]+)$'), self.handle_open_close),
+            (re.compile(r'^/v2/aggs/grouped/locale/(?:us|global)/market/(?P<market>stocks|fx|crypto)/(?P<day>[^/]+)$'), self.handle_grouped_daily),
+            (re.compile(r'^/v2/snapshot/locale/(?:us|global)/markets/(?P<market>stocks|forex|crypto)/tickers$'), self.handle_snapshots),
+            (re.compile(r'^/v2/snapshot/locale/(?:us|global)/markets/(?P<market>stocks|forex|crypto)/tickers/(?P<ticker>[^/]+)$'), self.handle_snapshot),
+            (re.compile(r'^/v2/snapshot/locale/(?:us|global)/markets/(?P<market>stocks|forex|crypto)/(?P<direction>gainers|losers)$'), self.handle_movers),
+            (re.compile(r'^/v3/snapshot/indices$'), self.handle_index_snapshots),
+            (re.compile(r'^/v1/conversion/(?P<from_currency>[A-Z]+)/(?P<to_currency>[A-Z]+)$'), self.handle_conversion),
+            (re.compile(r'^/v3/(?P<kind>trades|quotes)/(?P<ticker>[^/]+)$'), self.handle_ticks),
+            (re.compile(r'^/v3/reference/exchanges$'), self.handle_exchanges),
+            (re.compile(r'^/v3/reference/conditions$'), self.handle_conditions),
+            (re.compile(r'^/v1/marketstatus/now$'), lambda query: (200, load_fixture('market_status'), {})),
+            (re.compile(r'^/v1/marketstatus/upcoming$'), lambda query: (200, load_fixture('market_holidays'), {})),
+            (re.compile(r'^/v3/reference/options/contracts$'), self.handle_options_contracts),
+            (re.compile(r'^/v3/snapshot/options/(?P<underlying>[^/]+)$'), self.handle_option_chain),
+            (re.compile(r'^/v3/reference/tickers$'), self.handle_tickers),
+            (re.compile(r'^/v3/reference/tickers/(?P<ticker>[^/]+)$'), self.handle_ticker_details),
+            (re.compile(r'^/v1/related-companies/(?P<ticker>[^/]+)$'), self.handle_related_companies),
+            (re.compile(r'^/vX/reference/financials$'), self.list_handler('financials', '/vX/reference/financials', ticker_key='tickers')),
+            (re.compile(r'^/v3/reference/splits$'), self.list_handler('splits', '/v3/reference/splits')),
+            (re.compile(r'^/v3/reference/dividends$'), self.list_handler('dividends', '/v3/reference/dividends')),
+            (re.compile(r'^/v2/reference/news$'), self.list_handler('news', '/v2/reference/news', ticker_key='tickers')),
+        ]
+
+    @property
+    def base_url(self):
+        host, port = self.httpd.server_address[:2]
+        return f"http://{host}:{port}"
+
+    def start(self):
+        self.thread = threading.Thread(target=self.httpd.serve_forever, daemon=True)
+        self.thread.start()
+        return self
+
+    def stop(self):
+        self.httpd.shutdown()
+        self.httpd.server_close()
+
+    def __enter__(self):
+        return self.start()
+
+    def __exit__(self, *exc_info):
+        self.stop()
+
+    # Queue a canned response for a path; responses are served in order and the last one repeats
+    def add_response(self, path, status=200, body=None, headers=None):
+        with self.lock:
+            self.overrides.setdefault(path, []).append((status, body if body is not None else {}, headers or {}))
+
+    # Forget canned responses and recorded requests
+    def reset(self):
+        with self.lock:
+            self.overrides.clear()
+            self.requests.clear()
+
+    # Paths of the requests received so far
+    def request_paths(self):
+        with self.lock:
+            return [path for path, _ in self.requests]
+
+    # Query parameters of the requests received so far
+    def request_queries(self):
+        with self.lock:
+            return [query for _, query in self.requests]
+
+    def _make_handler(self):
+        server = self
+
+        class Handler(BaseHTTPRequestHandler):
+            def do_GET(self):
+                parsed = urlparse(self.path)
+                query = {key: values[-1] for key, values in parse_qs(parsed.query).items()}
+                status, body, headers = server.dispatch(parsed.path, query)
+                payload = json.dumps(body).encode('utf-8')
+                self.send_response(status)
+                self.send_header('Content-Type', 'application/json')
+                self.send_header('Content-Length', str(len(payload)))
+                for key, value in headers.items():
+                    self.send_header(key, value)
+                self.end_headers()
+                self.wfile.write(payload)
+
+            def log_message(self, format, *args):
+                pass
+
+        return Handler
+
+    # Route a request to a canned response or a fixture handler
+    def dispatch(self, path, query):
+        with self.lock:
+            self.requests.append((path, query))
+            queued = self.overrides.get(path)
+            if queued:
+                return queued.pop(0) if len(queued) > 1 else queued[0]
+        if not query.get('apiKey'):
+            return 401, {'status': 'ERROR', 'request_id': 'mock', 'error': 'API Key was not provided'}, {}
+        for pattern, handler in self.routes:
+            match = pattern.match(path)
+            if match:
+                return handler(query, **match.groupdict())
+        return 404, {'status': 'NOT_FOUND', 'request_id': 'mock', 'message': 'Not found'}, {}
+
+    # Serve a slice of results with a next_url cursor, like Polygon's v3 endpoints
+    def paginate(self, path, query, results, default_limit=10):
+        limit = int(query.get('limit', default_limit))
+        offset = int(query.get('cursor', 0))
+        body = {'status': 'OK', 'request_id': 'mock', 'results': results[offset:offset + limit]}
+        body['count'] = len(body['results'])
+        if offset + limit < len(results):
+            next_query = {key: value for key, value in query.items() if key != 'apiKey'}
+            next_query['cursor'] = offset + limit
+            body['next_url'] = f"{self.base_url}{path}?{urlencode(next_query)}"
+        return 200, body, {}
+
+    def handle_aggs(self, query, ticker, multiplier, timespan, from_date, to_date):
+        fixture = load_fixture(f"aggs_{ticker}_{timespan}") or {'ticker': ticker, 'results': []}
+        # Forex and crypto days start at midnight UTC, stock and index days at midnight US/Eastern
+        timezone = ZoneInfo('UTC') if ticker[:2] in ('C:', 'X:') else MARKET_TIMEZONE
+        start = day_start_ms(from_date, timezone)
+        end = day_start_ms((date.fromisoformat(to_date) + timedelta(days=1)).isoformat(), timezone)
+        results = [bar for bar in fixture.get('results', []) if start <= bar['t'] < end]
+        if query.get('sort') == 'desc':
+            results.reverse()
+        path = f"/v2/aggs/ticker/{ticker}/range/{multiplier}/{timespan}/{from_date}/{to_date}"
+        status, body, headers = self.paginate(path, query, results, default_limit=5000)
+        body.update({'ticker': ticker, 'adjusted': query.get('adjusted', 'true') == 'true', 'queryCount': len(results), 'resultsCount': body['count']})
+        return status, body, headers
+
+    # Indicators over the whole recording of a ticker's bars, then cut to the requested dates (timestamp.gte=YYYY-MM-DD and timestamp.lte)
+    def handle_indicator(self, query, indicator, ticker):
+        bars = (load_fixture(f"aggs_{ticker}_{query.get('timespan', 'day')}") or {}).get('results', [])
+        prices = [bar[query.get('series_type', 'close')[0]] for bar in bars]
+        results = [dict(value, timestamp=bar['t']) for bar, value in zip(bars, indicator_values(indicator, prices, query)) if value is not None]
+        timezone = ZoneInfo('UTC') if ticker[:2] in ('C:', 'X:') else MARKET_TIMEZONE
+        if 'timestamp.gte' in query:
+            results = [value for value in results if value['timestamp'] >= day_start_ms(query['timestamp.gte'], timezone)]
+        if 'timestamp.lte' in query:
+            end = day_start_ms((date.fromisoformat(query['timestamp.lte']) + timedelta(days=1)).isoformat(), timezone)
+            results = [value for value in results if value['timestamp'] < end]
+        if query.get('order', 'desc') == 'desc':
+            results.reverse()
+        path = f"/v1/indicators/{indicator}/{ticker}"
+        status, body, headers = self.paginate(path, query, results)
+        body['results'] = {'underlying': {'url': f"{self.base_url}/v2/aggs/ticker/{ticker}/range/1/{query.get('timespan', 'day')}"}, 'values': body['results']}
+        return status, body, headers
+
+    # The latest recorded daily bar stands in for the previous trading day
+    def handle_previous_close(self, query, ticker):
+        bars = (load_fixture(f"aggs_{ticker}_day") or {}).get('results', [])
+        results = [dict(bars[-1], T=ticker)] if bars else []
+        return 200, {'ticker': ticker, 'status': 'OK', 'request_id': 'mock', 'queryCount': len(results), 'resultsCount': len(results),
+                     'adjusted': query.get('adjusted', 'true') == 'true', 'results': results}, {}
+
+    def handle_open_close(self, query, ticker, day):
+        fixture = load_fixture(f"open_close_{ticker}_{day}")
+        if fixture is None:
+            return 404, {'status': 'NOT_FOUND', 'request_id': 'mock', 'message': 'Data not found.'}, {}
+        return 200, fixture, {}
+
+    # Days without a recording are treated as market holidays, for which Polygon returns no results
+    def handle_grouped_daily(self, query, market, day):
+        fixture = load_fixture(f"grouped_{day}" if market == 'stocks' else f"grouped_{market}_{day}")
+        if fixture is None:
+            return 200, {'status': 'OK', 'request_id': 'mock', 'queryCount': 0, 'resultsCount': 0, 'adjusted': True}, {}
+        return 200, fixture, {}
+
+    def handle_snapshots(self, query, market):
+        snapshots = snapshot_fixture(market)
+        if query.get('tickers'):
+            requested = query['tickers'].split(',')
+            snapshots = [snapshot for snapshot in snapshots if snapshot['ticker'] in requested]
+        return 200, {'status': 'OK', 'request_id': 'mock', 'count': len(snapshots), 'tickers': snapshots}, {}
+
+    def handle_snapshot(self, query, market, ticker):
+        snapshots = [snapshot for snapshot in snapshot_fixture(market) if snapshot['ticker'] == ticker]
+        if not snapshots:
+            return 404, {'status': 'NOT_FOUND', 'request_id': 'mock', 'message': 'Ticker not found.'}, {}
+        return 200, {'status': 'OK', 'request_id': 'mock', 'ticker': snapshots[0]}, {}
+
+    # Index snapshots; like Polygon, unknown tickers come back as error items instead of failing the request
+    def handle_index_snapshots(self, query):
+        snapshots = load_fixture('snapshot_indices')['results']
+        if query.get('ticker.any_of'):
+            requested = query['ticker.any_of'].split(',')
+            known = {snapshot['ticker']: snapshot for snapshot in snapshots}
+            snapshots = [known.get(ticker, {'ticker': ticker, 'error': 'NOT_FOUND', 'message': 'Ticker not found.'}) for ticker in requested]
+        return self.paginate('/v3/snapshot/indices', query, snapshots)
+
+    # Top 20 movers of the recorded snapshots
+    def handle_movers(self, query, market, direction):
+        snapshots = snapshot_fixture(market)
+        if direction == 'gainers':
+            movers = sorted((s for s in snapshots if s['todaysChangePerc'] > 0), key=lambda s: s['todaysChangePerc'], reverse=True)
+        else:
+            movers = sorted((s for s in snapshots if s['todaysChangePerc'] < 0), key=lambda s: s['todaysChangePerc'])
+        return 200, {'status': 'OK', 'request_id': 'mock', 'tickers': movers[:20]}, {}
+
+    # Convert at the middle of the last recorded forex quote of the pair, or of the inverse pair
+    def handle_conversion(self, query, from_currency, to_currency):
+        quotes = {snapshot['ticker']: snapshot['lastQuote'] for snapshot in snapshot_fixture('forex')}
+        quote = quotes.get(f"C:{from_currency}{to_currency}") or quotes.get(f"C:{to_currency}{from_currency}")
+        if quote is None:
+            return 404, {'status': 'NOT_FOUND', 'request_id': 'mock', 'message': 'Currency pair not found.'}, {}
+        rate = (quote['a'] + quote['b']) / 2
+        if f"C:{from_currency}{to_currency}" not in quotes:
+            rate = 1 / rate
+        amount = float(query.get('amount', 1))
+        return 200, {'status': 'success', 'request_id': 'mock', 'from': from_currency, 'to': to_currency, 'symbol': f"{from_currency}/{to_currency}",
+                     'initialAmount': amount, 'converted': round(amount * rate, int(query.get('precision', 2))),
+                     'last': {'ask': quote['a'], 'bid': quote['b'], 'exchange': quote['x'], 'timestamp': quote['t']}}, {}
+
+    # Trades and quotes filtered on their SIP timestamp (timestamp.gte=<nanoseconds> and the like)
+    def handle_ticks(self, query, kind, ticker):
+        results = (load_fixture(f"{kind}_{ticker}") or {}).get('results', [])
+        compare = {'gt': int.__gt__, 'gte': int.__ge__, 'lt': int.__lt__, 'lte': int.__le__}
+        for operator, function in compare.items():
+            if f'timestamp.{operator}' in query:
+                bound = int(query[f'timestamp.{operator}'])
+                results = [tick for tick in results if function(tick['sip_timestamp'], bound)]
+        if query.get('order') == 'desc':
+            results = results[::-1]
+        return self.paginate(f"/v3/{kind}/{ticker}", query, results, default_limit=1000)
+
+    def handle_exchanges(self, query):
+        results = [item for item in load_fixture('exchanges')['results'] if item['asset_class'] == query.get('asset_class', item['asset_class'])]
+        return 200, {'status': 'OK', 'request_id': 'mock', 'count': len(results), 'results': results}, {}
+
+    def handle_conditions(self, query):
+        results = [item for item in load_fixture('conditions')['results'] if item['asset_class'] == query.get('asset_class', item['asset_class'])]
+        if query.get('data_type'):
+            results = [item for item in results if query['data_type'] in item['data_types']]
+        return self.paginate('/v3/reference/conditions', query, results)
+
+    # Keep the options contracts matching the expiration date, contract type and strike price filters of a query
+    @staticmethod
+    def filter_options(contracts, query):
+        for key in ('expiration_date', 'contract_type'):
+            if query.get(key):
+                contracts = [contract for contract in contracts if contract[key] == query[key]]
+        compare = {'gt': float.__gt__, 'gte': float.__ge__, 'lt': float.__lt__, 'lte': float.__le__}
+        for operator, function in compare.items():
+            if f'strike_price.{operator}' in query:
+                bound = float(query[f'strike_price.{operator}'])
+                contracts = [contract for contract in contracts if function(float(contract['strike_price']), bound)]
+        return contracts
+
+    # Every recorded contract is unexpired, so expired=true lists none
+    def handle_options_contracts(self, query):
+        results = (load_fixture('options_contracts') or {}).get('results', [])
+        results = [item for item in results if item['underlying_ticker'] == query.get('underlying_ticker', item['underlying_ticker'])]
+        if query.get('expired') == 'true':
+            results = []
+        return self.paginate('/v3/reference/options/contracts', query, self.filter_options(results, query))
+
+    def handle_option_chain(self, query, underlying):
+        fixture = load_fixture(f"option_chain_{underlying}")
+        if fixture is None:
+            return 200, {'status': 'OK', 'request_id': 'mock', 'results': []}, {}
+        contracts = self.filter_options([item['details'] for item in fixture['results']], query)
+        tickers = {contract['ticker'] for contract in contracts}
+        results = [item for item in fixture['results'] if item['details']['ticker'] in tickers]
+        return self.paginate(f"/v3/snapshot/options/{underlying}", query, results)
+
+    # Ticker search: search matches the symbol or company name, the other filters match exactly
+    def handle_tickers(self, query):
+        results = load_fixture('tickers')['results']
+        search = query.get('search', '').lower()
+        if search:
+            results = [item for item in results if search in item['ticker'].lower() or search in item['name'].lower()]
+        for key, field in (('market', 'market'), ('type', 'type'), ('exchange', 'primary_exchange'), ('ticker', 'ticker')):
+            if query.get(key):
+                results = [item for item in results if item.get(field) == query[key]]
+        if 'active' in query:
+            results = [item for item in results if item['active'] == (query['active'] == 'true')]
+        return self.paginate('/v3/reference/tickers', query, results, default_limit=100)
+
+    def handle_ticker_details(self, query, ticker):
+        fixture = load_fixture(f"ticker_details_{ticker}")
+        if fixture is None:
+            return 404, {'status': 'NOT_FOUND', 'request_id': 'mock', 'message': 'Ticker not found.'}, {}
+        return 200, fixture, {}
+
+    def handle_related_companies(self, query, ticker):
+        fixture = load_fixture(f"related_companies_{ticker}")
+        if fixture is None:
+            return 404, {'status': 'NOT_FOUND', 'request_id': 'mock', 'message': 'Ticker not found.'}, {}
+        return 200, fixture, {}
+
+    # Build a handler serving a list endpoint from its fixture, filtered like Polygon does
+    def list_handler(self, name, path, ticker_key='ticker'):
+        return lambda query: self.handle_list(name, path, query, ticker_key)
+
+    def handle_list(self, name, path, query, ticker_key='ticker'):
+        results = load_fixture(name)['results']
+        ticker = query.get('ticker')
+        if ticker:
+            results = [item for item in results if ticker in (item.get(ticker_key) if isinstance(item.get(ticker_key), list) else [item.get(ticker_key)])]
+        timeframe = query.get('timeframe')
+        if timeframe:
+            results = [item for item in results if item.get('timeframe') == timeframe]
+        for key, value in query.items():
+            # Range filters such as execution_date.gte=2020-01-01
+            field, _, operator = key.partition('.')
+            if operator in ('gt', 'gte', 'lt', 'lte'):
+                compare = {'gt': str.__gt__, 'gte': str.__ge__, 'lt': str.__lt__, 'lte': str.__le__}[operator]
+                results = [item for item in results if compare(item.get(field, ''), value)]
+        return self.paginate(path, query, results)
+
+
+# Run the mock server standalone: POLYGON_BASE_URL=http://127.0.0.1:8765 streamlit run src/main.py
+if __name__ == '__main__':
+    parser = argparse.ArgumentParser(description='Serve recorded Polygon fixtures on a local port.')
+    parser.add_argument('--host', default='127.0.0.1')
+    parser.add_argument('--port', type=int, default=8765)
+    args = parser.parse_args()
+    server = MockPolygonServer(args.host, args.port)
+    print(f"Mock Polygon server listening on {server.base_url}")
+    try:
+        server.httpd.serve_forever()
+    except KeyboardInterrupt:
+        server.httpd.server_close()
@@ -0,0 +1,122 @@
+import argparse
+import json
+import os
+import threading
+import time
+from websockets.exceptions import ConnectionClosed
+from websockets.sync.server import serve
+
+# Recorded WebSocket events, one file per cluster: stream_stocks.json, stream_crypto.json...
+FIXTURES_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'fixtures')
+
+
+# Load the recorded events of a cluster (empty when there is no recording)
+def load_recording(cluster):
+    path = os.path.join(FIXTURES_DIR, f"stream_{cluster}.json")
+    if not os.path.exists(path):
+        return []
+    with open(path, encoding='utf-8') as f:
+        return json.load(f)
+
+
+# Subscription key of an event, e.g. 'AM.AAPL', 'XT.BTC-USD' or 'C.EUR/USD'
+def event_key(event):
+    return f"{event['ev']}.{event.get('sym') or event.get('pair') or event.get('p')}"
+
+
+# Time an event is sent in Unix milliseconds, used to pace the replay: aggregates at the end of their bar ('e'), trades and quotes at 't'
+# ('s' is the bar start of aggregates but the size of trades)
+def event_time(event):
+    return event['e'] if 'e' in event else event['t']
+
+
+# Local stand-in for Polygon's WebSocket feed (wss://socket.polygon.io/{cluster}) that replays recorded events
+# It follows Polygon's protocol: connected status, auth action, subscribe action, then arrays of events
+# speed scales the recorded gaps between events (0 sends them as fast as possible); api_key, when set, is the only key accepted
+# frames are raw messages sent ahead of the recording once subscribed, e.g. malformed ones
+class ReplayServer:
+    def __init__(self, host='127.0.0.1', port=0, speed=0, api_key=None, frames=()):
+        self.speed = speed
+        self.api_key = api_key
+        self.frames = list(frames)
+        self.connections = 0
+        self.lock = threading.Lock()
+        self.server = serve(self.handle, host, port)
+        self.thread = None
+
+    @property
+    def base_url(self):
+        host, port = self.server.socket.getsockname()[:2]
+        return f"ws://{host}:{port}"
+
+    def start(self):
+        self.thread = threading.Thread(target=self.server.serve_forever, daemon=True)
+        self.thread.start()
+        return self
+
+    def stop(self):
+        self.server.shutdown()
+
+    def __enter__(self):
+        return self.start()
+
+    def __exit__(self, *exc_info):
+        self.stop()
+
+    @staticmethod
+    def status(connection, status, message):
+        connection.send(json.dumps([{'ev': 'status', 'status': status, 'message': message}]))
+
+    def handle(self, connection):
+        with self.lock:
+            self.connections += 1
+        cluster = connection.request.path.strip('/')
+        self.status(connection, 'connected', 'Connected Successfully')
+        try:
+            subscribed = set()
+            authenticated = False
+            # Wait for the key and the first subscription
+            while not subscribed:
+                action = json.loads(connection.recv())
+                if action.get('action') == 'auth':
+                    if not action.get('params') or (self.api_key and action['params'] != self.api_key):
+                        self.status(connection, 'auth_failed', 'authentication failed')
+                        connection.close()
+                        return
+                    authenticated = True
+                    self.status(connection, 'auth_success', 'authenticated')
+                elif action.get('action') == 'subscribe' and authenticated:
+                    subscribed.update(action.get('params', '').split(','))
+                    for key in sorted(subscribed):
+                        self.status(connection, 'success', f"subscribed to: {key}")
+            for frame in self.frames:
+                connection.send(frame)
+            previous = None
+            for event in load_recording(cluster):
+                key = event_key(event)
+                if key not in subscribed and f"{event['ev']}.*" not in subscribed:
+                    continue
+                if self.speed and previous is not None:
+                    time.sleep(max(0, event_time(event) - previous) / 1000 / self.speed)
+                previous = event_time(event)
+                connection.send(json.dumps([event]))
+            # Keep the connection open like the live feed until the client leaves
+            for _ in connection:
+                pass
+        except ConnectionClosed:
+            pass
+
+
+# Run the replay server standalone: POLYGON_STREAM_URL=ws://127.0.0.1:8766 streamlit run src/main.py
+if __name__ == '__main__':
+    parser = argparse.ArgumentParser(description="Replay recorded Polygon WebSocket events on a local port.")
+    parser.add_argument('--host', default='127.0.0.1')
+    parser.add_argument('--port', type=int, default=8766)
+    parser.add_argument('--speed', type=float, default=1.0, help='Replay speed relative to the recording, 0 for no delays')
+    args = parser.parse_args()
+    server = ReplayServer(args.host, args.port, speed=args.speed)
+    print(f"Replay server listening on {server.base_url}")
+    try:
+        server.server.serve_forever()
+    except KeyboardInterrupt:
+        server.server.shutdown()
@@ -0,0 +1,441 @@
+import os
+import time
+from datetime import date, timedelta
+import pytest
+from streamlit.testing.v1 import AppTest
+from cache import ResponseCache
+from config.display_config import format_number
+from polygon_client import PolygonClient
+
+APP_PATH = os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))), 'src', 'main.py')
+
+
+# Start the app as an authenticated user with a test API key
+def make_app():
+    app = AppTest.from_file(APP_PATH, default_timeout=30)
+    app.secrets['API_KEY'] = 'test-key'
+    app.session_state['authenticated'] = True
+    return app
+
+
+# The pickers offer free text until the ticker lists are loaded in the background, so load them into the app's cache up front
+@pytest.fixture
+def ticker_lists(mock_polygon):
+    client = PolygonClient('test-key', cache=ResponseCache())
+    for market in ('stocks', 'indices', 'crypto'):
+        client.get_ticker_universe(market)
+
+
+# Switch the sidebar market and page and rerun the app
+def open_page(app, page, market='stocks'):
+    app.run()
+    if market != 'stocks':
+        app.sidebar.selectbox(key='market').set_value(market).run()
+    app.sidebar.selectbox(key='page').set_value(page).run()
+    return app
+
+
+def test_landing_page_shows_latest_news(mock_polygon):
+    app = make_app().run()
+
+    assert not app.exception
+    assert [header.value for header in app.header] == ['Latest News']
+    assert any('Apple shares edge higher' in markdown.value for markdown in app.markdown)
+
+
+def test_market_snapshot_page(mock_polygon, ticker_lists):
+    app = open_page(make_app(), 'Market Snapshot')
+    app.multiselect(key='watchlist').set_value(['AAPL', 'PLTR', 'QQQ']).run()
+
+    assert not app.exception
+    assert 'QQQ' in app.warning[0].value
+    watchlist, gainers, losers = (dataframe.value for dataframe in app.dataframe)
+    # Sorted by the day's change, biggest gain first
+    assert list(watchlist.index) == ['PLTR', 'AAPL']
+    assert gainers.index[0] == 'PLTR'
+    assert losers.index[0] == 'TSLA'
+
+
+def test_sidebar_shows_market_status(mock_polygon):
+    app = make_app().run()
+
+    sidebar_text = [markdown.value for markdown in app.sidebar.markdown] + [caption.value for caption in app.sidebar.caption]
+    assert ':green[●] **Market open**' in sidebar_text
+    assert 'Next holiday: National Day of Mourning on 2025-01-09' in sidebar_text
+
+
+def test_market_selector_changes_pages(mock_polygon):
+    app = open_page(make_app(), 'Select', market='fx')
+
+    pages = app.sidebar.selectbox(key='page').options
+    assert 'Currency Conversion' in pages
+    assert 'Company Detail' not in pages
+    assert any('Forex market open' in markdown.value for markdown in app.sidebar.markdown)
+
+
+def test_crypto_market_snapshot_page(mock_polygon, ticker_lists):
+    app = open_page(make_app(), 'Market Snapshot', market='crypto')
+
+    assert not app.exception
+    assert app.multiselect(key='watchlist_crypto').value == ['X:BTCUSD', 'X:ETHUSD']
+    assert list(app.dataframe[0].value.index) == ['X:ETHUSD', 'X:BTCUSD']
+
+
+def test_currency_conversion_page(mock_polygon):
+    app = open_page(make_app(), 'Currency Conversion', market='fx')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert app.metric[0].label == '1,000.00 EUR'
+    assert app.metric[0].value == '1,095.00 USD'
+
+
+def test_live_chart_page_streams_from_the_replay_server(mock_polygon, replay_polygon):
+    app = open_page(make_app(), 'Live Chart')
+    app.button[0].click().run()
+    stream = app.session_state['live_stream']
+    deadline = time.monotonic() + 5
+    while len(stream.buffer.to_frame('AAPL')) < 10 and time.monotonic() < deadline:
+        time.sleep(0.05)
+    app.button[1].click().run()
+
+    assert not app.exception
+    assert stream.subscription() == 'AM.AAPL,T.AAPL,Q.AAPL'
+    assert not stream.running
+    assert app.metric[0].value == '187.09'
+    assert len(app.get('plotly_chart')) == 1
+
+
+def test_leaving_the_live_chart_page_stops_the_stream(mock_polygon, replay_polygon):
+    app = open_page(make_app(), 'Live Chart')
+    app.button[0].click().run()
+    stream = app.session_state['live_stream']
+    assert stream.running
+
+    app.sidebar.selectbox(key='page').set_value('Market Snapshot').run()
+
+    assert not app.exception
+    assert not stream.running
+    assert 'live_stream' not in app.session_state
+
+
+def test_historical_dates_must_be_in_order(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.date_input[0].set_value(date(2024, 2, 1))
+    app.date_input[1].set_value(date(2024, 1, 1)).run()
+
+    assert app.error[0].value == 'The from date must not be after the to date.'
+    assert app.button[0].disabled
+
+
+def test_historical_stock_data_page(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert len(app.dataframe) == 1
+    assert len(app.dataframe[0].value) == 9
+
+
+def test_crypto_historical_data_page(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data', market='crypto')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert app.header[0].value == 'Historical Crypto Data'
+    # Crypto prices are never split adjusted
+    assert len(app.checkbox) == 0
+    assert len(app.dataframe[0].value) == 31
+
+
+def test_historical_stock_data_page_with_indicators(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.multiselect[0].set_value(['SMA', 'RSI'])
+    app.button[0].click().run()
+    app.number_input(key='indicator_SMA_window').set_value(5).run()
+
+    assert not app.exception
+    assert len(app.get('plotly_chart')) == 1
+
+
+def test_historical_indicators_cross_check(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.multiselect[0].set_value(['SMA', 'RSI', 'Stochastic']).run()
+    app.number_input(key='indicator_SMA_window').set_value(3)
+    app.number_input(key='indicator_RSI_window').set_value(3)
+    app.checkbox(key='validate_indicators').check()
+    app.button[0].click().run()
+
+    assert not app.exception
+    # Polygon has no server-side stochastic oscillator
+    assert [success.value for success in app.success] == ['SMA: all 7 server values match the local computation.',
+                                                          'RSI: all 6 server values match the local computation.']
+
+
+def test_historical_stock_data_page_without_results(mock_polygon, ticker_lists):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.selectbox(key='historical_ticker').set_value('QQQ')
+    app.button[0].click().run()
+
+    assert [error.value for error in app.error] == ['No historical data found.']
+
+
+def test_historical_stock_data_page_with_resampled_bars(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.radio[0].set_value('Resampled from minute bars').run()
+    # Minute bars default to the last few trading days
+    assert date.today() - timedelta(days=14) <= app.date_input[0].value < date.today()
+    app.date_input[0].set_value(date(2024, 1, 2))
+    app.date_input[1].set_value(date(2024, 1, 3))
+    app.button[0].click().run()
+
+    assert not app.exception
+    # 78 five-minute bars per regular session
+    assert len(app.dataframe[0].value) == 156
+
+
+def test_historical_comparison_mode(mock_polygon, ticker_lists):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.toggle[0].set_value(True).run()
+    app.multiselect(key='comparison_tickers').set_value(['AAPL', 'MSFT', 'QQQ'])
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert 'QQQ' in app.warning[0].value
+    # Cumulative returns, relative strength and correlation charts
+    assert len(app.get('plotly_chart')) == 3
+    assert sorted(app.dataframe[0].value.index) == ['AAPL', 'MSFT', 'SPY']
+
+
+def test_comparison_against_an_index_benchmark(mock_polygon, ticker_lists):
+    app = open_page(make_app(), 'Historical Stock Data')
+    app.toggle[0].set_value(True).run()
+    app.multiselect(key='comparison_tickers').set_value(['AAPL', 'MSFT'])
+    app.selectbox(key='benchmark').set_value('I:SPX')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert len(app.get('plotly_chart')) == 3
+    assert sorted(app.dataframe[0].value.index) == ['AAPL', 'I:SPX', 'MSFT']
+
+
+def test_index_dashboard_page(mock_polygon):
+    app = open_page(make_app(), 'Index Dashboard', market='indices')
+    app.date_input(key='dashboard_from').set_value(date(2024, 1, 1))
+    app.date_input(key='dashboard_to').set_value(date(2024, 1, 31))
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert [metric.label for metric in app.metric] == ['I:SPX', 'I:NDX', 'I:DJI', 'I:VIX']
+    assert app.metric[0].value == '4,783.83'
+    # Index returns, with the VIX charted on its own
+    assert len(app.get('plotly_chart')) == 2
+    assert len(app.dataframe[0].value) == 4
+    assert any('Indices open' in markdown.value for markdown in app.sidebar.markdown)
+
+
+def test_index_historical_data_has_no_volume_indicators(mock_polygon):
+    app = open_page(make_app(), 'Historical Stock Data', market='indices')
+
+    assert 'VWAP' not in app.multiselect[0].options
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert app.header[0].value == 'Historical Indices Data'
+    assert 'Volume' not in app.dataframe[0].value.columns
+
+
+def test_tick_data_page(mock_polygon):
+    app = open_page(make_app(), 'Tick Data')
+    app.date_input[0].set_value(date(2024, 1, 2))
+    app.button[0].click().run()
+
+    assert not app.exception
+    # Trade size histogram and NBBO spread chart
+    assert len(app.get('plotly_chart')) == 2
+    trades, quotes = (dataframe.value for dataframe in app.dataframe)
+    assert len(trades) == 240
+    assert 'Spread' in quotes.columns
+
+
+def test_options_chain_page(mock_polygon):
+    app = open_page(make_app(), 'Options Chain')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert app.selectbox(key='options_expiration').options == ['2024-01-19', '2024-02-16']
+    chain = app.dataframe[0].value
+    assert list(chain.index) == [175.0, 180.0, 185.0, 190.0, 195.0]
+    # The far strikes have no greeks from the API and are priced locally
+    assert not chain[['Call IV', 'Put IV', 'Call Delta', 'Put Delta']].isna().any().any()
+    assert any('computed locally' in caption.value for caption in app.caption)
+
+
+def test_black_scholes_calculator(mock_polygon):
+    app = open_page(make_app(), 'Options Chain')
+    app.number_input(key='calculator_market_price').set_value(5.0).run()
+
+    assert not app.exception
+    assert app.metric[0].label == 'Theoretical Price'
+    assert app.metric[-1].label == 'Implied Volatility'
+
+
+def test_market_day_page(mock_polygon):
+    app = open_page(make_app(), 'Market Day')
+    app.date_input[0].set_value(date(2024, 1, 12))
+    app.button[0].click().run()
+
+    assert not app.exception
+    gainers, losers, most_active = (dataframe.value for dataframe in app.dataframe)
+    assert gainers.index[0] == 'PLTR'
+    assert losers.index[0] == 'TSLA'
+    assert most_active.index[0] == 'TSLA'
+    assert [metric.label for metric in app.metric][:4] == ['Pre-Market', 'Open', 'Close', 'After Hours']
+
+
+def test_market_day_page_on_a_holiday(mock_polygon):
+    app = open_page(make_app(), 'Market Day')
+    app.date_input[0].set_value(date(2024, 1, 15))
+    app.button[0].click().run()
+
+    assert 'market may have been closed' in app.error[0].value
+    assert [warning.value for warning in app.warning] == [
+        'The market is closed on 2024-01-15 (Martin Luther King Jr. Day).',
+        'No open/close data found for AAPL on 2024-01-15.',
+    ]
+
+
+def test_ticker_search_page(mock_polygon):
+    app = open_page(make_app(), 'Ticker Search')
+    app.text_input[0].set_value('s')
+    app.selectbox(key='search_type').set_value('ETF')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert list(app.dataframe[0].value.index) == ['QQQ', 'SPY']
+
+
+def test_ticker_pickers_offer_the_ticker_list(mock_polygon, ticker_lists):
+    app = open_page(make_app(), 'Company Detail')
+
+    picker = app.selectbox(key='company_ticker')
+    assert picker.value == 'AAPL'
+    assert 'TSLA - Tesla, Inc. Common Stock' in picker.options
+    # Inactive tickers are left out
+    assert not any(option.startswith('TWTR') for option in picker.options)
+
+
+def test_ticker_pickers_use_free_text_until_the_list_is_loaded(mock_polygon):
+    app = open_page(make_app(), 'Company Detail')
+
+    assert app.text_input(key='company_ticker').value == 'AAPL'
+    deadline = time.monotonic() + 5
+    while not any(box.key == 'company_ticker' for box in app.selectbox) and time.monotonic() < deadline:
+        time.sleep(0.05)
+        app.run()
+    assert app.selectbox(key='company_ticker').value == 'AAPL'
+
+
+def test_ticker_list_refresh(mock_polygon):
+    app = open_page(make_app(), 'Ticker Search')
+    app.button[1].click().run()
+
+    assert app.success[0].value == 'Loaded 13 stocks tickers.'
+
+
+def test_company_detail_page(mock_polygon):
+    app = open_page(make_app(), 'Company Detail')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert any('Related News for AAPL' in subheader.value for subheader in app.subheader)
+
+
+def test_company_detail_page_shows_api_errors(mock_polygon):
+    mock_polygon.add_response('/v3/reference/tickers/AAPL', 500, {'status': 'ERROR'})
+    app = open_page(make_app(), 'Company Detail')
+    app.button[0].click().run()
+
+    assert 'status code 500' in app.error[0].value
+
+
+def test_company_financials_page(mock_polygon):
+    app = open_page(make_app(), 'Company Financials Data')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert len(app.dataframe[0].value) == 3
+
+
+def test_company_financials_trend_charts(mock_polygon):
+    app = open_page(make_app(), 'Company Financials Data')
+    app.button[0].click().run()
+    assert 'Select a timeframe' in app.info[0].value
+
+    app.selectbox(key='financials_timeframe').set_value('quarterly')
+    app.button[0].click().run()
+    assert not app.exception
+    assert len(app.get('plotly_chart')) == 4
+
+
+def test_peer_comparison_seeded_with_related_companies(mock_polygon):
+    app = open_page(make_app(), 'Company Financials Data')
+    app.toggle(key='peer_mode').set_value(True).run()
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert 'AMZN' in app.warning[0].value
+    ranking = app.dataframe[0].value
+    assert list(ranking.index) == ['MSFT', 'AAPL', 'GOOGL']
+    # Year-over-year growth needs a report a year earlier, which the recorded reports lack, so it is not charted
+    assert len(app.get('plotly_chart')) == 3
+
+
+def test_valuation_page(mock_polygon):
+    app = open_page(make_app(), 'Valuation')
+    app.radio(key='valuation_timeframe').set_value('Annual')
+    app.button[0].click().run()
+
+    assert not app.exception
+    multiples = {metric.label: metric.value for metric in app.metric}
+    assert multiples['P/E'] == format_number(185.92 / 6.13)
+    assert multiples['Dividend Yield (%)'] == format_number(0.72 / 185.92 * 100)
+    assert multiples['Last Close'] == '185.92'
+    assert len(app.dataframe) == 4  # Multiples, projection, summary and sensitivity
+    # The recorded filings have cash flows but no cash balance
+    assert 'Not available from the filings, so estimated: revenue growth (5% a year), net debt (zero).' in [caption.value for caption in app.caption]
+
+    # A discount rate below the terminal growth rate cannot be valued
+    app.number_input(key='dcf_discount_rate_AAPL_annual').set_value(2.0).run()
+    assert 'discount rate must be higher' in app.error[0].value
+
+
+def test_financial_statements_page(mock_polygon):
+    app = open_page(make_app(), 'Financial Statements')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert [expander.label for expander in app.expander] == ['Income Statement', 'Balance Sheet', 'Cash Flow Statement', 'Comprehensive Income']
+    income_statement = app.dataframe[0].value
+    assert list(income_statement.columns) == ['Unit', 'Q1 2024', 'Q4 2023']
+    assert len(income_statement) == 5
+
+    # Expanding the line items reuses the last query
+    app.toggle(key='statements_detailed').set_value(True).run()
+    assert len(app.dataframe[0].value) == 10
+
+
+def test_stock_splits_page(mock_polygon):
+    app = open_page(make_app(), 'Stock Splits Data')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert len(app.dataframe[0].value) == 4
+
+
+def test_dividends_page(mock_polygon):
+    app = open_page(make_app(), 'Dividends Data')
+    app.button[0].click().run()
+
+    assert not app.exception
+    assert len(app.dataframe[0].value) == 5
@@ -0,0 +1,194 @@
+from datetime import date, datetime
+import pytest
+import config.cache_config as cache_config
+import cache as cache_module
+from cache import CacheMissError, MARKET_TIMEZONE, ResponseCache
+from http_client import HttpSession
+import polygon_client
+from polygon_client import PolygonClient
+
+
+def test_put_and_get_round_trip(cache):
+    cache.put('news', {'ticker': 'AAPL'}, [{'title': 'Hello'}])
+
+    assert cache.get('news', {'ticker': 'AAPL'}) == [{'title': 'Hello'}]
+    assert cache.get('news', {'ticker': 'MSFT'}) is None
+
+
+def test_entries_expire_after_ttl(tmp_path, monkeypatch):
+    cache = ResponseCache(str(tmp_path / 'ttl.sqlite'), ttl={'news': 60})
+    now = [1000.0]
+    monkeypatch.setattr(cache_module.time, 'time', lambda: now[0])
+    cache.put('news', {}, ['fresh'])
+
+    now[0] += 61
+
+    assert cache.get('news', {}) is None
+    assert cache.get('news', {}, allow_stale=True) == ['fresh']
+
+
+def test_zero_ttl_disables_caching(tmp_path):
+    cache = ResponseCache(str(tmp_path / 'off.sqlite'), ttl={'news': 0})
+    cache.put('news', {}, ['ignored'])
+
+    assert cache.get('news', {}, allow_stale=True) is None
+
+
+def test_missing_bar_ranges(cache):
+    cache.put_bars('AAPL:1:day:adjusted', date(2024, 1, 10), date(2024, 1, 20), [])
+
+    assert cache.missing_bar_ranges('AAPL:1:day:adjusted', date(2024, 1, 1), date(2024, 1, 31)) == [
+        (date(2024, 1, 1), date(2024, 1, 9)),
+        (date(2024, 1, 21), date(2024, 1, 31)),
+    ]
+    assert cache.missing_bar_ranges('AAPL:1:day:adjusted', date(2024, 1, 12), date(2024, 1, 15)) == []
+
+
+def test_aggregates_only_fetch_missing_ranges(cached_client, mock_polygon):
+    cached_client.get_aggregates('AAPL', '2024-01-04', '2024-01-08')
+    bars = cached_client.get_aggregates('AAPL', '2024-01-01', '2024-01-31')
+
+    assert len(bars) == 9
+    assert mock_polygon.request_paths() == [
+        '/v2/aggs/ticker/AAPL/range/1/day/2024-01-04/2024-01-08',
+        '/v2/aggs/ticker/AAPL/range/1/day/2024-01-01/2024-01-03',
+        '/v2/aggs/ticker/AAPL/range/1/day/2024-01-09/2024-01-31',
+    ]
+
+
+def test_multiple_unit_bars_are_cached_as_whole_responses(cached_client, mock_polygon):
+    cached_client.get_aggregates('AAPL', '2024-01-02', '2024-01-02', timespan='minute', multiplier=5)
+    cached_client.get_aggregates('AAPL', '2024-01-02', '2024-01-03', timespan='minute', multiplier=5)
+    cached_client.get_aggregates('AAPL', '2024-01-02', '2024-01-03', timespan='minute', multiplier=5)
+
+    # The wider range is fetched in full rather than topped up from the end of the first one
+    assert mock_polygon.request_paths() == [
+        '/v2/aggs/ticker/AAPL/range/5/minute/2024-01-02/2024-01-02',
+        '/v2/aggs/ticker/AAPL/range/5/minute/2024-01-02/2024-01-03',
+    ]
+
+
+def test_crypto_bars_are_cached_by_utc_day(cached_client, mock_polygon):
+    cached_client.get_aggregates('X:BTCUSD', '2024-01-01', '2024-01-15')
+    bars = cached_client.get_aggregates('X:BTCUSD', '2024-01-01', '2024-01-31')
+
+    # The bar stamped at midnight UTC on January 1st belongs to that day rather than to December 31st in New York
+    assert len(bars) == 31
+    assert bars[0].timestamp == 1704067200000
+    assert mock_polygon.request_paths()[-1] == '/v2/aggs/ticker/X:BTCUSD/range/1/day/2024-01-16/2024-01-31'
+
+
+def test_adjusted_bars_stored_before_a_split_are_fetched_again(cached_client, mock_polygon, monkeypatch):
+    real_time = cache_module.time.time
+    # Store the bars on 2020-08-28, the last trading day before AAPL's 4-for-1 split
+    monkeypatch.setattr(cache_module.time, 'time', lambda: 1598644800.0)
+    cached_client.get_aggregates('AAPL', '2024-01-01', '2024-01-31')
+    cached_client.get_aggregates('MSFT', '2024-01-01', '2024-01-31')
+    monkeypatch.setattr(cache_module.time, 'time', real_time)
+
+    assert len(cached_client.get_aggregates('AAPL', '2024-01-01', '2024-01-31')) == 9
+    cached_client.get_aggregates('MSFT', '2024-01-01', '2024-01-31')
+
+    paths = mock_polygon.request_paths()
+    assert paths.count('/v2/aggs/ticker/AAPL/range/1/day/2024-01-01/2024-01-31') == 2
+    # MSFT has not split since, so its bars are kept
+    assert paths.count('/v2/aggs/ticker/MSFT/range/1/day/2024-01-01/2024-01-31') == 1
+    assert paths.count('/v3/reference/splits') == 2
+
+
+def test_adjusted_bars_are_checked_for_splits_once_a_day(mock_polygon, tmp_path, monkeypatch):
+    # Split responses are not cached here, so every check would reach the API
+    cache = ResponseCache(str(tmp_path / 'splits.sqlite'), ttl=dict(cache_config.CACHE_TTL, splits=0))
+    client = PolygonClient('test-key', base_url=mock_polygon.base_url, session=HttpSession(max_retries=0), cache=cache)
+    real_time = cache_module.time.time
+    monkeypatch.setattr(cache_module.time, 'time', lambda: 1598644800.0)
+    client.get_aggregates('MSFT', '2024-01-01', '2024-01-31')
+    monkeypatch.setattr(cache_module.time, 'time', real_time)
+
+    client.get_aggregates('MSFT', '2024-01-01', '2024-01-31')
+    client.get_aggregates('MSFT', '2024-01-01', '2024-01-31')
+
+    assert mock_polygon.request_paths().count('/v3/reference/splits') == 1
+    assert cache.bars_checked_on('MSFT:1:day:adjusted') == datetime.now(MARKET_TIMEZONE).date()
+
+
+def test_reference_data_is_served_from_cache(cached_client, mock_polygon):
+    first = cached_client.get_ticker_details('AAPL')
+    second = cached_client.get_ticker_details('AAPL')
+
+    assert first == second
+    assert len(mock_polygon.request_paths()) == 1
+
+
+def test_offline_mode_replays_cache_without_network(cached_client, cache, mock_polygon):
+    cached_client.get_aggregates('AAPL', '2024-01-01', '2024-01-31')
+    cached_client.get_news('AAPL', limit=5)
+    mock_polygon.reset()
+
+    offline = PolygonClient(None, session=HttpSession(max_retries=0), cache=cache, offline=True)
+
+    assert len(offline.get_aggregates('AAPL', '2024-01-01', '2024-02-29')) == 9
+    assert len(offline.get_news('AAPL', limit=5)) == 2
+    assert mock_polygon.request_paths() == []
+
+
+def test_offline_mode_raises_on_cache_miss(cache):
+    offline = PolygonClient(None, cache=cache, offline=True)
+
+    with pytest.raises(CacheMissError):
+        offline.get_news('AAPL')
+    with pytest.raises(CacheMissError):
+        offline.get_aggregates('AAPL', '2024-01-01', '2024-01-31')
+
+
+
+def test_ticker_universe_is_cached_until_refreshed(cached_client, mock_polygon):
+    assert len(cached_client.get_ticker_universe()) == 13
+    cached_client.get_ticker_universe()
+    assert len(mock_polygon.request_paths()) == 1
+
+    cached_client.get_ticker_universe(refresh=True)
+
+    assert len(mock_polygon.request_paths()) == 2
+
+
+def test_ticker_universe_is_capped_and_readable_from_cache_only(cached_client, mock_polygon):
+    with pytest.raises(CacheMissError):
+        cached_client.get_ticker_universe(cached_only=True, limit=5)
+    assert mock_polygon.request_paths() == []
+
+    assert len(cached_client.get_ticker_universe(limit=5)) == 5
+    assert [ticker.ticker for ticker in cached_client.get_ticker_universe(cached_only=True, limit=5)] == ['AAPL', 'AMD', 'AMZN', 'F', 'GOOGL']
+    assert len(mock_polygon.request_paths()) == 1
+
+# 2024-01-02 09:30 to 09:35 US/Eastern in Unix nanoseconds
+OPEN_NS, FIVE_PAST_NS = 1704205800000000000, 1704206100000000000
+
+
+def test_trades_are_streamed_into_the_tick_store(cached_client, cache, mock_polygon, monkeypatch):
+    monkeypatch.setitem(polygon_client.MAX_PAGE_SIZE, 'trades', 100)
+
+    trades = cached_client.get_trades('AAPL', OPEN_NS, FIVE_PAST_NS)
+
+    assert len(trades) == 240
+    assert len(mock_polygon.request_paths()) == 3
+    assert cache.has_tick_range('trades:AAPL', OPEN_NS, OPEN_NS + 60 * 10**9)
+    # A sub-range of a stored range is served without any request
+    assert len(cached_client.get_trades('AAPL', OPEN_NS, OPEN_NS + 60 * 10**9)) == 48
+    assert len(mock_polygon.request_paths()) == 3
+
+
+def test_trades_cut_short_by_the_limit_are_fetched_again(cached_client, cache, mock_polygon):
+    assert len(cached_client.get_trades('AAPL', OPEN_NS, FIVE_PAST_NS, limit=10)) == 10
+    assert not cache.has_tick_range('trades:AAPL', OPEN_NS, FIVE_PAST_NS)
+
+
+def test_offline_mode_serves_stored_ticks(cached_client, cache, mock_polygon):
+    cached_client.get_quotes('AAPL', OPEN_NS, FIVE_PAST_NS)
+    mock_polygon.reset()
+
+    offline = PolygonClient(None, cache=cache, offline=True)
+
+    assert len(offline.get_quotes('AAPL', OPEN_NS, FIVE_PAST_NS)) == 239
+    with pytest.raises(CacheMissError):
+        offline.get_trades('AAPL', OPEN_NS, FIVE_PAST_NS)
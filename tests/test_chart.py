@@ -0,0 +1,54 @@
+import pandas as pd
+from chart import build_candlestick_figure, build_spread_figure, build_financial_trend_figures
+
+
+def make_bars():
+    index = pd.DatetimeIndex(pd.bdate_range('2024-01-02', periods=40), name='Date')
+    close = pd.Series(range(40), index=index, dtype='float64') + 100
+    return pd.DataFrame({'Open': close - 1, 'High': close + 1, 'Low': close - 2, 'Close': close, 'Volume': 1000}, index=index)
+
+
+def test_price_and_volume_panes():
+    fig = build_candlestick_figure(make_bars())
+
+    assert [trace.type for trace in fig.data] == ['candlestick', 'bar']
+    assert fig.data[1].yaxis == 'y2'
+    assert set(fig.data[1].marker.color) == {'#26a69a'}
+
+
+def test_indicator_panes_follow_volume():
+    fig = build_candlestick_figure(make_bars(), indicators=[('SMA', {'window': 5}), ('RSI', {}), ('MACD', {})])
+
+    panes = {trace.name: trace.yaxis for trace in fig.data}
+    assert panes['SMA(5)'] == 'y'
+    assert panes['RSI(14)'] == 'y3'
+    assert panes['MACD(12, 26, 9) Histogram'] == 'y4'
+
+
+def test_volume_pane_can_be_hidden():
+    fig = build_candlestick_figure(make_bars(), show_volume=False)
+
+    assert [trace.type for trace in fig.data] == ['candlestick']
+
+
+def test_spread_pane_below_bid_and_ask():
+    index = pd.DatetimeIndex(pd.date_range('2024-01-02 09:30', periods=5, freq='s'), name='Time')
+    quotes = pd.DataFrame({'Bid': [10.0, 10.01, 10.0, 10.02, 10.01], 'Ask': [10.02, 10.03, 10.03, 10.03, 10.02]}, index=index)
+    quotes['Spread'] = quotes['Ask'] - quotes['Bid']
+
+    fig = build_spread_figure(quotes)
+
+    assert [(trace.name, trace.yaxis) for trace in fig.data] == [('Bid', 'y'), ('Ask', 'y'), ('Spread', 'y2')]
+
+
+def test_financial_trends_skip_series_without_values():
+    history = pd.DataFrame({
+        'Period': ['Q4 2023', 'Q1 2024'], 'Revenue': [89.5, 119.6], 'Net Income': [23.0, 33.9], 'Diluted EPS': [1.46, 2.18],
+        'Gross Margin (%)': [48.5, 45.9], 'Operating Margin (%)': [33.5, 33.8], 'Net Margin (%)': [25.6, 28.4],
+        'Operating Cash Flow': [21.6, 39.9], 'Free Cash Flow': [float('nan'), float('nan')],
+    })
+    figures = build_financial_trend_figures(history)
+
+    assert [fig.layout.title.text for fig in figures] == ['Revenue and Net Income', 'Diluted EPS', 'Margins', 'Cash Flow']
+    assert [trace.name for trace in figures[-1].data] == ['Operating Cash Flow']
+    assert list(figures[0].data[0].x) == ['Q4 2023', 'Q1 2024']
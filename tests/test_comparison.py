@@ -0,0 +1,55 @@
+import pandas as pd
+import pytest
+from comparison import align_close_prices, rebased_returns, relative_strength, return_correlation, summarize_returns
+
+
+def make_frame(closes, dates):
+    return pd.DataFrame({'Close': closes}, index=pd.DatetimeIndex(pd.to_datetime(dates), name='Date'))
+
+
+@pytest.fixture
+def prices():
+    dates = ['2024-01-02', '2024-01-03', '2024-01-04']
+    return align_close_prices({
+        'AAA': make_frame([10.0, 11.0, 12.0], dates),
+        'BBB': make_frame([20.0, 20.0, 30.0], dates),
+        'SPY': make_frame([100.0, 110.0, 120.0], dates),
+    })
+
+
+def test_align_keeps_common_dates_only():
+    prices = align_close_prices({
+        'AAA': make_frame([1.0, 2.0, 3.0], ['2024-01-02', '2024-01-03', '2024-01-04']),
+        'BBB': make_frame([5.0, 6.0], ['2024-01-03', '2024-01-04']),
+    })
+
+    assert list(prices.columns) == ['AAA', 'BBB']
+    assert list(prices.index.strftime('%Y-%m-%d')) == ['2024-01-03', '2024-01-04']
+
+
+def test_rebased_returns_start_at_zero(prices):
+    returns = rebased_returns(prices)
+
+    assert (returns.iloc[0] == 0).all()
+    assert returns['BBB'].iloc[-1] == pytest.approx(0.5)
+
+
+def test_relative_strength_against_benchmark(prices):
+    strength = relative_strength(prices, 'SPY')
+
+    assert list(strength.columns) == ['AAA', 'BBB']
+    assert strength['AAA'].tolist() == pytest.approx([1.0, 1.0, 1.0])
+    assert strength['BBB'].iloc[-1] == pytest.approx(1.5 / 1.2)
+
+
+def test_return_correlation(prices):
+    corr = return_correlation(prices)
+
+    assert corr.loc['AAA', 'AAA'] == pytest.approx(1.0)
+    assert corr.shape == (3, 3)
+
+
+def test_summarize_returns(prices):
+    summary = summarize_returns(prices)
+
+    assert summary.loc['SPY', 'Total Return (%)'] == pytest.approx(20.0)
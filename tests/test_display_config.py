@@ -0,0 +1,21 @@
+import pandas as pd
+from config.display_config import format_number, build_column_config
+
+
+def test_format_number_uses_locale_separators():
+    assert format_number(1234567.891) == '1,234,567.89'
+    assert format_number(1234567.891, locale='de_DE') == '1.234.567,89'
+    assert format_number(1234, decimals=0, locale='fr_FR') == '1 234'
+    assert format_number(float('nan')) == ''
+
+
+def test_column_config_formats_dates_by_resolution():
+    df = pd.DataFrame(
+        {'Close': [1.0, 2.0], 'Filed': pd.to_datetime(['2024-01-02 10:30', '2024-01-03 11:00'])},
+        index=pd.DatetimeIndex(pd.to_datetime(['2024-01-02', '2024-01-03']), name='Date'),
+    )
+
+    config = build_column_config(df)
+
+    assert set(config) == {'Filed', '_index'}
+    assert build_column_config(df, styled=False).keys() == {'Close', 'Filed', '_index'}
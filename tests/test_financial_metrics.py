@@ -0,0 +1,104 @@
+import math
+import pandas as pd
+import pytest
+from mock_polygon import load_fixture
+from models import FinancialReport
+from financial_metrics import line_items, compute_metrics, financial_metrics, metrics_history, rank_peers
+
+
+def make_report(end_date, timeframe='quarterly', fiscal_period='Q1', **values):
+    return FinancialReport.from_api({
+        'end_date': end_date,
+        'timeframe': timeframe,
+        'fiscal_period': fiscal_period,
+        'financials': {'income_statement': {key: {'value': value} for key, value in values.items()}},
+    })
+
+
+@pytest.fixture
+def reports():
+    return [FinancialReport.from_api(item) for item in load_fixture('financials')['results'] if 'AAPL' in item['tickers']]
+
+
+def test_metrics_from_the_recorded_reports(reports):
+    metrics = compute_metrics(line_items(reports))
+    q1 = metrics.iloc[0]
+
+    assert q1['Gross Margin (%)'] == pytest.approx(54855 / 119575 * 100)
+    assert q1['Net Margin (%)'] == pytest.approx(33916 / 119575 * 100)
+    assert q1['ROE (%)'] == pytest.approx(33916 / 74100 * 100)
+    assert q1['ROIC (%)'] == pytest.approx(40373 * (1 - 6407 / 40323) / (74100 + 95088) * 100)
+    assert q1['Current Ratio'] == pytest.approx(143692 / 133973)
+    assert q1['Quick Ratio'] == pytest.approx((143692 - 6511) / 133973)
+    assert q1['Debt to Equity'] == pytest.approx(95088 / 74100)
+    # Investing brought cash in that quarter, so nothing is taken off the operating cash flow
+    assert q1['Free Cash Flow'] == 39895000000
+    # Interest expense is not in the recorded filings, so coverage is missing instead of computed from zero
+    assert math.isnan(q1['Interest Coverage'])
+    # The Q4 filing has no long-term debt
+    assert math.isnan(metrics.iloc[2]['Debt to Equity'])
+
+
+def test_free_cash_flow_roic_and_coverage_from_optional_items():
+    report = make_report('2024-03-31', revenues=100.0, operating_income_loss=30.0, income_loss_from_continuing_operations_before_tax=25.0,
+                         income_tax_expense_benefit=5.0, equity=100.0, long_term_debt=50.0, interest_expense_operating=5.0,
+                         net_cash_flow_from_operating_activities=20.0, net_cash_flow_from_investing_activities=-8.0)
+    metrics = compute_metrics(line_items([report])).iloc[0]
+
+    assert metrics['Free Cash Flow'] == 12.0
+    assert metrics['Debt to Equity'] == 0.5
+    assert metrics['Interest Coverage'] == 6.0
+    # Operating income after a 20% effective tax rate over equity plus debt
+    assert metrics['ROIC (%)'] == pytest.approx(30 * 0.8 / 150 * 100)
+
+
+def test_zero_denominators_give_missing_values():
+    metrics = compute_metrics(line_items([make_report('2024-03-31', revenues=0.0, net_income_loss=-5.0, equity=0.0)])).iloc[0]
+
+    assert math.isnan(metrics['Net Margin (%)'])
+    assert math.isnan(metrics['ROE (%)'])
+
+
+def test_growth_within_each_timeframe(reports):
+    metrics = financial_metrics(reports)
+
+    # Q1 2024 against Q4 2023; the annual report has no earlier annual report to compare with
+    assert metrics.loc[0, 'Revenue Growth PoP (%)'] == pytest.approx((119575 / 89498 - 1) * 100)
+    assert math.isnan(metrics.loc[1, 'Revenue Growth PoP (%)'])
+    assert math.isnan(metrics.loc[2, 'Revenue Growth PoP (%)'])
+    assert math.isnan(metrics.loc[0, 'Revenue Growth YoY (%)'])
+
+
+def test_year_over_year_growth_matches_the_period_a_year_earlier():
+    reports = [
+        make_report('2024-03-30', revenues=120.0, net_income_loss=-2.0),
+        make_report('2023-12-30', fiscal_period='Q4', revenues=110.0, net_income_loss=-6.0),
+        make_report('2023-04-01', revenues=100.0, net_income_loss=-4.0),
+    ]
+    metrics = financial_metrics(reports)
+
+    assert metrics.loc[0, 'Revenue Growth YoY (%)'] == pytest.approx(20.0)
+    # A smaller loss is growth
+    assert metrics.loc[0, 'Net Income Growth YoY (%)'] == pytest.approx(50.0)
+    assert metrics.loc[0, 'Net Income Growth PoP (%)'] == pytest.approx(200 / 3)
+    assert math.isnan(metrics.loc[1, 'Revenue Growth YoY (%)'])
+
+
+def test_metrics_history_is_oldest_first(reports):
+    history = metrics_history([report for report in reports if report.timeframe == 'quarterly'])
+
+    assert list(history['Period']) == ['Q4 2023', 'Q1 2024']
+    assert list(history['Revenue']) == [89498000000, 119575000000]
+    assert history['Diluted EPS'].iloc[-1] == 2.18
+
+
+def test_rank_peers_by_average_rank():
+    table = pd.DataFrame({'ROE (%)': [45.0, 9.0, 7.0], 'Net Margin (%)': [28.0, 35.0, 24.0], 'Debt to Equity': [1.5, 0.2, float('nan')]},
+                         index=pd.Index(['AAPL', 'MSFT', 'GOOGL'], name='Ticker'))
+    ranking = rank_peers(table, ['ROE (%)', 'Net Margin (%)', 'Debt to Equity'])
+
+    assert list(ranking.index) == ['MSFT', 'AAPL', 'GOOGL']
+    # Lower leverage ranks better, and a missing value is left out of the ranking
+    assert list(ranking['Debt to Equity Rank'].iloc[:2]) == [1.0, 2.0]
+    assert math.isnan(ranking.loc['GOOGL', 'Debt to Equity Rank'])
+    assert ranking.loc['GOOGL', 'Average Rank'] == 3.0
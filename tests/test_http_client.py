@@ -0,0 +1,74 @@
+import socket
+import pytest
+import requests
+from http_client import HttpSession, TokenBucket, parse_retry_after
+
+
+def test_retries_on_429_and_honors_retry_after(mock_polygon):
+    mock_polygon.add_response('/limited', 429, {'status': 'ERROR'}, {'Retry-After': '3'})
+    mock_polygon.add_response('/limited', 200, {'status': 'OK'})
+    sleeps = []
+    session = HttpSession(max_retries=2, sleep=sleeps.append)
+
+    response = session.get(f"{mock_polygon.base_url}/limited")
+
+    assert response.status_code == 200
+    assert sleeps == [3.0]
+    assert mock_polygon.request_paths() == ['/limited', '/limited']
+
+
+def test_gives_up_after_max_retries_with_capped_backoff(mock_polygon):
+    mock_polygon.add_response('/down', 503, {'status': 'ERROR'})
+    sleeps = []
+    session = HttpSession(max_retries=3, backoff_base=1, backoff_max=2, sleep=sleeps.append)
+
+    response = session.get(f"{mock_polygon.base_url}/down")
+
+    assert response.status_code == 503
+    assert len(sleeps) == 3
+    assert all(0 <= delay <= 2 for delay in sleeps)
+    assert len(mock_polygon.request_paths()) == 4
+
+
+def test_does_not_retry_client_errors(mock_polygon):
+    mock_polygon.add_response('/missing', 404, {'status': 'NOT_FOUND'})
+    sleeps = []
+    session = HttpSession(max_retries=3, sleep=sleeps.append)
+
+    assert session.get(f"{mock_polygon.base_url}/missing").status_code == 404
+    assert sleeps == []
+
+
+def test_retries_connection_errors_then_raises():
+    # Reserve a free port and close it so nothing is listening there
+    with socket.socket() as sock:
+        sock.bind(('127.0.0.1', 0))
+        port = sock.getsockname()[1]
+    sleeps = []
+    session = HttpSession(max_retries=1, sleep=sleeps.append)
+
+    with pytest.raises(requests.ConnectionError):
+        session.get(f"http://127.0.0.1:{port}/")
+    assert len(sleeps) == 1
+
+
+def test_token_bucket_waits_for_refill():
+    now = [0.0]
+    sleeps = []
+
+    def sleep(seconds):
+        sleeps.append(seconds)
+        now[0] += seconds
+
+    bucket = TokenBucket(2, 60, clock=lambda: now[0], sleep=sleep)
+    for _ in range(3):
+        bucket.acquire()
+
+    assert sleeps == [pytest.approx(30.0)]
+
+
+def test_parse_retry_after():
+    assert parse_retry_after('12') == 12.0
+    assert parse_retry_after('Wed, 21 Oct 2015 07:28:00 GMT') == 0.0
+    assert parse_retry_after('soon') is None
+    assert parse_retry_after(None) is None
@@ -0,0 +1,93 @@
+import pandas as pd
+import pytest
+import indicators
+
+
+# Ten daily bars with closes 1..10, a two point high-low range and constant volume
+@pytest.fixture
+def bars():
+    close = pd.Series(range(1, 11), dtype='float64')
+    index = pd.DatetimeIndex(pd.date_range('2024-01-01', periods=10, freq='D'), name='Date')
+    return pd.DataFrame({'Open': close.values, 'High': close.values + 1, 'Low': close.values - 1,
+                         'Close': close.values, 'Volume': 100}, index=index)
+
+
+def test_sma(bars):
+    result = indicators.sma(bars, window=3)
+
+    assert result.iloc[:2].isna().all()
+    assert result.iloc[-1] == 9.0
+
+
+def test_ema_of_constant_series_is_constant(bars):
+    bars['Close'] = 5.0
+
+    assert (indicators.ema(bars, window=3).dropna() == 5.0).all()
+
+
+def test_bollinger_bands_collapse_without_volatility(bars):
+    bars['Close'] = 5.0
+    bands = indicators.bollinger_bands(bars, window=3).dropna()
+
+    assert (bands['Upper'] == bands['Lower']).all()
+    assert (bands['Middle'] == 5.0).all()
+
+
+def test_rsi_bounds(bars):
+    assert (indicators.rsi(bars, window=3).dropna() == 100).all()
+
+    bars['Close'] = bars['Close'][::-1].values
+    assert (indicators.rsi(bars, window=3).dropna() == 0).all()
+
+
+def test_macd_of_constant_series_is_zero(bars):
+    bars['Close'] = 5.0
+
+    assert (indicators.macd(bars).abs() < 1e-12).all().all()
+
+
+def test_stochastic(bars):
+    result = indicators.stochastic(bars, k_window=3, d_window=2)
+
+    # Last close 10 within the 3-bar range of 7 to 11
+    assert result['%K'].iloc[-1] == 75.0
+    assert result['%D'].iloc[-1] == 75.0
+
+
+def test_vwap_is_anchored_for_daily_bars(bars):
+    assert indicators.vwap(bars).iloc[-1] == 5.5
+
+
+def test_vwap_resets_each_day_for_intraday_bars(bars):
+    bars.index = pd.DatetimeIndex(pd.to_datetime(['2024-01-02 09:30', '2024-01-02 09:31', '2024-01-02 09:32', '2024-01-02 09:33', '2024-01-02 09:34',
+                                                  '2024-01-03 09:30', '2024-01-03 09:31', '2024-01-03 09:32', '2024-01-03 09:33', '2024-01-03 09:34']))
+    result = indicators.vwap(bars)
+
+    assert result.iloc[4] == 3.0
+    assert result.iloc[5] == 6.0
+
+
+def test_compute_indicator_labels_columns(bars):
+    assert list(indicators.compute_indicator(bars, 'SMA', window=3).columns) == ['SMA(3)']
+    assert list(indicators.compute_indicator(bars, 'Bollinger Bands').columns) == [
+        'Bollinger Bands(20, 2.0) Upper', 'Bollinger Bands(20, 2.0) Middle', 'Bollinger Bands(20, 2.0) Lower']
+
+
+def test_local_indicator_uses_server_column_names(bars):
+    assert list(indicators.local_indicator(bars, 'SMA', window=3).columns) == ['SMA']
+    assert list(indicators.local_indicator(bars, 'MACD').columns) == ['MACD', 'Signal', 'Histogram']
+    # Computed on another price series
+    assert indicators.local_indicator(bars, 'SMA', 'high', window=3)['SMA'].iloc[-1] == 10.0
+
+
+def test_compare_indicator_flags_differences_and_missing_values(bars):
+    local = indicators.local_indicator(bars, 'SMA', window=3)
+    server = local.dropna().copy()
+    server.iloc[1, 0] += 0.5
+    server.loc[pd.Timestamp('2024-01-11')] = 11.0  # A bar the local data does not have
+
+    report = indicators.compare_indicator(server, local)
+
+    assert list(report['Match']) == [True, False] + [True] * 6 + [False]
+    assert report['SMA Difference'].iloc[1] == pytest.approx(-0.5)
+    assert indicators.warmup_bars('MACD') == 260
@@ -0,0 +1,85 @@
+from datetime import date
+import pandas as pd
+from market_calendar import easter_sunday, get_rangebreaks, holidays_between, market_closed_reason, previous_trading_day, us_market_holidays
+
+
+def test_easter_sunday():
+    assert easter_sunday(2024) == date(2024, 3, 31)
+    assert easter_sunday(2025) == date(2025, 4, 20)
+
+
+def test_holidays_2024():
+    assert sorted(us_market_holidays(2024)) == [
+        date(2024, 1, 1), date(2024, 1, 15), date(2024, 2, 19), date(2024, 3, 29), date(2024, 5, 27),
+        date(2024, 6, 19), date(2024, 7, 4), date(2024, 9, 2), date(2024, 11, 28), date(2024, 12, 25),
+    ]
+
+
+def test_weekend_holidays_are_observed():
+    holidays_2022 = us_market_holidays(2022)
+
+    # New Year's Day 2022 fell on a Saturday and was not observed
+    assert date(2021, 12, 31) not in holidays_2022
+    assert holidays_2022[date(2022, 6, 20)] == 'Juneteenth'
+    assert holidays_2022[date(2022, 12, 26)] == 'Christmas Day'
+
+
+def test_holidays_between():
+    assert holidays_between(date(2024, 1, 1), date(2024, 2, 1)) == [date(2024, 1, 1), date(2024, 1, 15)]
+
+
+
+def test_previous_trading_day_skips_weekends_and_holidays():
+    assert previous_trading_day(date(2024, 1, 12)) == date(2024, 1, 11)
+    # Monday 2024-01-15 was Martin Luther King Jr. Day
+    assert previous_trading_day('2024-01-16') == date(2024, 1, 12)
+
+
+def test_market_closed_reason():
+    assert market_closed_reason(date(2024, 1, 13)) == 'Weekend'
+    assert market_closed_reason(date(2024, 1, 15)) == 'Martin Luther King Jr. Day'
+    assert market_closed_reason(date(2024, 1, 16)) is None
+    # Closures announced by the exchanges that the built-in calendar cannot know about
+    assert market_closed_reason(date(2025, 1, 9), {date(2025, 1, 9): 'National Day of Mourning'}) == 'National Day of Mourning'
+
+
+def test_forex_and_crypto_ignore_the_holiday_calendar():
+    # Forex trades on Martin Luther King Jr. Day but not at weekends, crypto trades every day
+    assert market_closed_reason(date(2024, 1, 15), market='fx') is None
+    assert market_closed_reason(date(2024, 1, 13), market='fx') == 'Weekend'
+    assert market_closed_reason(date(2024, 1, 13), market='crypto') is None
+    assert previous_trading_day('2024-01-16', market='fx') == date(2024, 1, 15)
+    assert previous_trading_day('2024-01-14', market='crypto') == date(2024, 1, 13)
+
+
+def test_rangebreaks_include_extra_holidays():
+    index = pd.DatetimeIndex(pd.bdate_range('2025-01-06', '2025-01-10'))
+
+    assert dict(values=['2025-01-09']) in get_rangebreaks(index, {date(2025, 1, 9): 'National Day of Mourning'})
+
+
+def test_daily_rangebreaks_hide_weekends_and_holidays():
+    index = pd.DatetimeIndex(pd.bdate_range('2024-01-02', '2024-01-31'))
+
+    assert get_rangebreaks(index) == [dict(bounds=['sat', 'mon']), dict(values=['2024-01-15'])]
+
+
+def test_intraday_rangebreaks_hide_overnight_hours():
+    index = pd.DatetimeIndex(pd.date_range('2024-01-02 09:30', periods=10, freq='min'))
+
+    assert dict(bounds=[20, 4], pattern='hour') in get_rangebreaks(index)
+
+
+def test_weekly_bars_have_no_rangebreaks():
+    index = pd.DatetimeIndex(pd.date_range('2024-01-01', periods=10, freq='W'))
+
+    assert get_rangebreaks(index) == []
+
+
+def test_crypto_charts_have_no_rangebreaks_and_forex_charts_hide_weekends():
+    daily = pd.DatetimeIndex(pd.date_range('2024-01-01', '2024-01-31'))
+    intraday = pd.DatetimeIndex(pd.date_range('2024-01-12 09:30', periods=10, freq='min'))
+
+    assert get_rangebreaks(daily, market='crypto') == []
+    assert get_rangebreaks(daily, market='fx') == [dict(bounds=['sat', 'mon'])]
+    assert get_rangebreaks(intraday, market='fx') == [dict(bounds=['sat', 'sun'])]
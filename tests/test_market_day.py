@@ -0,0 +1,33 @@
+import pandas as pd
+import pytest
+from market_day import add_daily_change, top_movers
+
+
+@pytest.fixture
+def market():
+    bars = pd.DataFrame({'Close': [11.0, 9.0, 0.5, 50.0], 'Volume': [1000, 3000, 9000, 2000]},
+                        index=pd.Index(['AAA', 'BBB', 'PENNY', 'NEW'], name='Ticker'))
+    previous = pd.DataFrame({'Close': [10.0, 10.0, 0.25]}, index=pd.Index(['AAA', 'BBB', 'PENNY'], name='Ticker'))
+    return add_daily_change(bars, previous)
+
+
+def test_daily_change_from_previous_close(market):
+    assert market.loc['AAA', 'Change'] == pytest.approx(1.0)
+    assert market.loc['BBB', 'Change (%)'] == pytest.approx(-10.0)
+    # Newly listed tickers have no previous close to compare with
+    assert pd.isna(market.loc['NEW', 'Change (%)'])
+
+
+def test_top_movers(market):
+    movers = top_movers(market, count=5)
+
+    assert list(movers['Top Gainers'].index) == ['PENNY', 'AAA']
+    assert list(movers['Top Losers'].index) == ['BBB']
+    assert list(movers['Most Active'].index) == ['PENNY', 'BBB', 'NEW', 'AAA']
+
+
+def test_top_movers_price_floor(market):
+    movers = top_movers(market, count=5, min_price=1.0)
+
+    assert 'PENNY' not in movers['Top Gainers'].index
+    assert 'PENNY' not in movers['Most Active'].index
@@ -0,0 +1,21 @@
+import pytest
+from markets import currency_pair, get_market, market_of
+
+
+def test_market_of_ticker_prefix():
+    assert market_of('AAPL').name == 'stocks'
+    assert market_of('C:EURUSD').name == 'fx'
+    assert market_of('X:BTCUSD').name == 'crypto'
+    assert not market_of('X:BTCUSD').split_adjusted
+    assert market_of('I:SPX').name == 'indices'
+    assert market_of('I:SPX').snapshot_path is None
+
+
+def test_currency_pair():
+    assert currency_pair('C:EURUSD') == ('EUR', 'USD')
+    assert currency_pair('X:DOGEUSD') == ('DOGE', 'USD')
+
+
+def test_unknown_market():
+    with pytest.raises(ValueError):
+        get_market('bonds')
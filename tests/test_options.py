@@ -0,0 +1,78 @@
+import math
+import pandas as pd
+import pytest
+from options import black_scholes_price, black_scholes_greeks, implied_volatility, years_to_expiry, fill_missing_greeks, chain_by_strike
+
+
+def test_black_scholes_textbook_values():
+    # Hull's example: spot 100, strike 100, one year, 5% rate, 20% volatility
+    assert black_scholes_price(100, 100, 1, 0.05, 0.2, 'call') == pytest.approx(10.4506, abs=1e-4)
+    assert black_scholes_price(100, 100, 1, 0.05, 0.2, 'put') == pytest.approx(5.5735, abs=1e-4)
+    greeks = black_scholes_greeks(100, 100, 1, 0.05, 0.2, 'call')
+    assert greeks['delta'] == pytest.approx(0.6368, abs=1e-4)
+    assert greeks['gamma'] == pytest.approx(0.01876, abs=1e-5)
+
+
+def test_put_call_parity():
+    call = black_scholes_price(185.92, 190, 0.1, 0.05, 0.25, 'call', dividend_yield=0.01)
+    put = black_scholes_price(185.92, 190, 0.1, 0.05, 0.25, 'put', dividend_yield=0.01)
+
+    assert call - put == pytest.approx(185.92 * math.exp(-0.01 * 0.1) - 190 * math.exp(-0.05 * 0.1))
+
+
+def test_expired_options_are_worth_their_intrinsic_value():
+    assert black_scholes_price(110, 100, 0, 0.05, 0.2, 'call') == pytest.approx(10)
+    assert black_scholes_price(110, 100, 0, 0.05, 0.2, 'put') == 0
+    assert math.isnan(black_scholes_greeks(110, 100, 0, 0.05, 0.2)['delta'])
+
+
+def test_implied_volatility_round_trip():
+    price = black_scholes_price(185.92, 180, 0.05, 0.05, 0.31, 'put')
+
+    assert implied_volatility(price, 185.92, 180, 0.05, 0.05, 'put') == pytest.approx(0.31, abs=1e-5)
+    # Below the intrinsic value no volatility reproduces the price
+    assert implied_volatility(1.0, 185.92, 170, 0.05, 0.05, 'call') is None
+
+
+def test_years_to_expiry_counts_to_the_close():
+    as_of = pd.Timestamp('2024-01-12 16:00', tz='US/Eastern')
+
+    assert years_to_expiry('2024-01-19', as_of) == pytest.approx(7 / 365)
+    assert years_to_expiry('2024-01-12', as_of + pd.Timedelta(hours=1)) == 0
+
+
+@pytest.fixture
+def chain():
+    as_of = pd.Timestamp('2024-01-12 16:00', tz='US/Eastern')
+    years = years_to_expiry('2024-01-19', as_of)
+    rows = []
+    for contract_type in ('call', 'put'):
+        for strike in (180.0, 190.0):
+            price = black_scholes_price(185.0, strike, years, 0.05, 0.25, contract_type)
+            greeks = black_scholes_greeks(185.0, strike, years, 0.05, 0.25, contract_type)
+            rows.append({'Type': contract_type, 'Expiration': '2024-01-19', 'Strike': strike, 'Last': price, 'Bid': price - 0.05, 'Ask': price + 0.05,
+                         'Mid': price, 'IV': 0.25, 'Delta': greeks['delta'], 'Gamma': greeks['gamma'], 'Theta': greeks['theta'], 'Vega': greeks['vega'],
+                         'Open Interest': 100.0, 'Volume': 10.0})
+    df = pd.DataFrame(rows)
+    # The API left out the greeks of the 190 call
+    df.loc[1, ['IV', 'Delta', 'Gamma', 'Theta', 'Vega']] = float('nan')
+    return df, as_of
+
+
+def test_fill_missing_greeks_from_the_mid_price(chain):
+    df, as_of = chain
+    filled = fill_missing_greeks(df, 185.0, 0.05, as_of)
+
+    assert list(filled['Computed']) == [False, True, False, False]
+    assert filled.loc[1, 'IV'] == pytest.approx(0.25, abs=1e-4)
+    assert filled.loc[1, 'Delta'] == pytest.approx(black_scholes_greeks(185.0, 190.0, 7 / 365, 0.05, 0.25)['delta'], abs=1e-3)
+    assert filled['Theoretical'].to_numpy() == pytest.approx(df['Mid'].to_numpy(), abs=1e-3)
+
+
+def test_chain_by_strike_lays_calls_beside_puts(chain):
+    df, _ = chain
+    table = chain_by_strike(df, columns=('Bid', 'Delta'))
+
+    assert list(table.index) == [180.0, 190.0]
+    assert list(table.columns) == ['Call Bid', 'Call Delta', 'Put Bid', 'Put Delta']
+    assert table.loc[180.0, 'Call Delta'] > 0 > table.loc[180.0, 'Put Delta']
@@ -0,0 +1,218 @@
+from datetime import date
+import pandas as pd
+import pytest
+import polygon_api
+
+
+def test_historical_data_is_typed():
+    df = polygon_api.get_historical_data_as_df('AAPL', '2024-01-01', '2024-01-31', True, 'day', 'test-key')
+
+    assert isinstance(df.index, pd.DatetimeIndex)
+    assert df.index.name == 'Date'
+    assert str(df.index.tz) == 'US/Eastern'
+    assert df.index[0] == pd.Timestamp('2024-01-02', tz='US/Eastern')
+    assert df['Close'].dtype == 'float64'
+    assert df['Volume'].dtype == 'int64'
+    assert df['Close'].iloc[0] == 185.64
+    assert df['VWAP'].iloc[0] == 185.9465
+    assert df['Transactions'].iloc[0] == 1008871
+
+
+def test_intraday_bars_keep_their_time():
+    df = polygon_api.get_historical_data_as_df('AAPL', '2024-01-02', '2024-01-03', True, 'minute', 'test-key')
+
+    assert df.index.is_unique
+    assert df.index[0] == pd.Timestamp('2024-01-02 08:00', tz='US/Eastern')
+    assert df.index[10] == pd.Timestamp('2024-01-02 09:30', tz='US/Eastern')
+
+
+def test_crypto_days_keep_their_utc_date():
+    df = polygon_api.get_historical_data_as_df('X:BTCUSD', '2024-01-01', '2024-01-31', True, 'day', 'test-key')
+
+    # Crypto trades at weekends too, in fractions of a coin
+    assert len(df) == 31
+    assert str(df.index.tz) == 'UTC'
+    assert df.index[0] == pd.Timestamp('2024-01-01', tz='UTC')
+    assert df['Volume'].dtype == 'float64'
+
+
+def test_index_bars_have_no_volume():
+    df = polygon_api.get_historical_data_as_df('I:SPX', '2024-01-01', '2024-01-31', True, 'day', 'test-key')
+
+    assert list(df.columns) == ['Open', 'High', 'Low', 'Close']
+    assert len(df) == 9
+    assert df.index[0] == pd.Timestamp('2024-01-02', tz='US/Eastern')
+
+
+def test_server_indicators_match_local_computation():
+    for name, params in [('SMA', {'window': 3}), ('EMA', {'window': 3}), ('RSI', {'window': 3}), ('MACD', {'fast': 2, 'slow': 3, 'signal': 2})]:
+        report = polygon_api.validate_indicator('AAPL', name, '2024-01-01', '2024-01-31', True, 'day', 'test-key', **params)
+
+        assert len(report) >= 6, name
+        assert report['Match'].all(), name
+    assert list(report.columns[:3]) == ['MACD Server', 'MACD Local', 'MACD Difference']
+
+
+def test_indicator_discrepancies_are_reported(mock_polygon):
+    # The server value of 2024-01-05 is off
+    mock_polygon.add_response('/v1/indicators/sma/AAPL', body={'status': 'OK', 'results': {'values': [
+        {'timestamp': 1704344400000, 'value': (185.64 + 184.25 + 181.91) / 3}, {'timestamp': 1704430800000, 'value': 200.0}]}})
+
+    report = polygon_api.validate_indicator('AAPL', 'SMA', '2024-01-01', '2024-01-31', True, 'day', 'test-key', window=3)
+
+    assert list(report['Match']) == [True, False]
+    assert report.index[1] == pd.Timestamp('2024-01-05', tz='US/Eastern')
+    assert report['SMA Difference'].iloc[1] == pytest.approx((184.25 + 181.91 + 181.18) / 3 - 200)
+
+
+def test_resampled_data_from_minute_bars(mock_polygon):
+    df = polygon_api.get_resampled_data_as_df('AAPL', '2024-01-02', '2024-01-03', True, '30 minutes', 'test-key')
+
+    # 13 half-hour bars per regular session
+    assert len(df) == 26
+    assert df.index[0] == pd.Timestamp('2024-01-02 09:30', tz='US/Eastern')
+    assert df['Open'].iloc[0] == 187.24
+    # Every interval is built from the same minute fetch
+    polygon_api.get_resampled_data_as_df('AAPL', '2024-01-02', '2024-01-03', True, '1 day', 'test-key', session='extended')
+    assert len(mock_polygon.request_paths()) == 1
+
+
+def test_resampled_daily_bars_include_extended_hours():
+    df = polygon_api.get_resampled_data_as_df('AAPL', '2024-01-02', '2024-01-03', True, '1 day', 'test-key', session='extended')
+
+    assert len(df) == 2
+    assert df['Volume'].iloc[0] == 62864758
+
+
+def test_historical_data_empty_results():
+    assert polygon_api.get_historical_data_as_df('NODATA', '2024-01-01', '2024-01-31', True, 'day', 'test-key').empty
+
+
+def test_market_day_change_from_previous_trading_day():
+    df = polygon_api.get_market_day_as_df('2024-01-12', True, 'test-key')
+
+    assert df.index.name == 'Ticker'
+    assert df.loc['AAPL', 'Previous Close'] == 185.59
+    assert df.loc['PLTR', 'Change (%)'] == pytest.approx((16.98 / 16.11 - 1) * 100)
+
+
+def test_market_day_on_a_holiday_is_empty():
+    assert polygon_api.get_market_day_as_df('2024-01-15', True, 'test-key').empty
+
+
+def test_daily_open_close_missing_day_returns_none():
+    assert polygon_api.get_daily_open_close('AAPL', '2024-01-15', True, 'test-key') is None
+
+
+def test_crypto_market_day_on_a_saturday():
+    df = polygon_api.get_market_day_as_df('2024-01-13', False, 'test-key', market='crypto')
+
+    assert df.loc['X:BTCUSD', 'Previous Close'] == 42830.5
+    assert df.loc['X:BTCUSD', 'Change'] == pytest.approx(99.5)
+
+
+def test_snapshots_table_skips_unknown_tickers():
+    df = polygon_api.get_snapshots_as_df(['AAPL', 'NOPE'], 'test-key')
+
+    assert list(df.index) == ['AAPL']
+    assert df.loc['AAPL', 'Change (%)'] == 0.1778
+    assert df.loc['AAPL', 'Updated'] == pd.Timestamp('2024-01-12 16:00', tz='US/Eastern')
+
+
+def test_index_snapshots_table_has_no_volume_or_quotes():
+    df = polygon_api.get_snapshots_as_df(['I:SPX', 'I:VIX'], 'test-key', 'indices')
+
+    assert list(df.index) == ['I:SPX', 'I:VIX']
+    assert 'Volume' not in df.columns and 'Bid' not in df.columns
+    assert df.loc['I:VIX', 'Previous Close'] == 12.44
+
+
+def test_trades_decode_exchanges_and_conditions():
+    df = polygon_api.get_trades_as_df('AAPL', 1704205800000000000, 1704206100000000000, 'test-key')
+
+    assert df.index.name == 'Time'
+    assert df.index[0] == pd.Timestamp(1704205800000112017, tz='UTC').tz_convert('US/Eastern')
+    # Names come from the exchanges reference data
+    assert df['Exchange'].iloc[0] == 'MEMX'
+    assert df['Conditions'].iloc[0] == 'Market Center Official Open, Market Center Opening Trade'
+
+
+def test_trades_fall_back_to_built_in_names(mock_polygon):
+    mock_polygon.add_response('/v3/reference/exchanges', 500, {'status': 'ERROR'})
+    mock_polygon.add_response('/v3/reference/conditions', 500, {'status': 'ERROR'})
+
+    df = polygon_api.get_trades_as_df('AAPL', 1704205800000000000, 1704206100000000000, 'test-key')
+
+    assert df['Exchange'].iloc[0] == 'Members Exchange'
+    assert df['Conditions'].iloc[0] == 'Market Center Official Open, Market Center Opening Trade'
+
+
+def test_quotes_have_spread():
+    df = polygon_api.get_quotes_as_df('AAPL', 1704205800000000000, 1704206100000000000, 'test-key')
+
+    assert (df['Spread'] == df['Ask'] - df['Bid']).all()
+    assert (df['Spread'] > 0).all()
+
+
+def test_option_expirations():
+    assert polygon_api.get_option_expirations('AAPL', 'test-key') == ['2024-01-19', '2024-02-16']
+
+
+def test_option_chain_table():
+    df = polygon_api.get_option_chain_as_df('AAPL', '2024-01-19', 'test-key')
+
+    assert df.index.name == 'Contract'
+    assert len(df) == 10
+    assert list(df['Type']) == ['call'] * 5 + ['put'] * 5
+    assert list(df.loc[df['Type'] == 'call', 'Strike']) == [175.0, 180.0, 185.0, 190.0, 195.0]
+    assert df.loc['O:AAPL240119C00185000', 'Underlying Updated'] == pd.Timestamp('2024-01-12 16:00', tz='US/Eastern')
+    # Greeks Polygon could not compute stay missing until filled in locally
+    assert pd.isna(df.loc['O:AAPL240119C00195000', 'IV'])
+
+
+def test_market_holidays_are_full_day_closures():
+    holidays = polygon_api.get_market_holidays('test-key')
+
+    assert holidays[date(2025, 1, 9)] == 'National Day of Mourning'
+    # Early closes are trading days
+    assert date(2025, 7, 3) not in holidays
+
+
+def test_market_status_unavailable(mock_polygon):
+    mock_polygon.add_response('/v1/marketstatus/now', 500, {'status': 'ERROR'})
+
+    assert polygon_api.get_market_status('test-key') is None
+
+
+def test_financials_dataframe_keeps_numbers_numeric():
+    data = polygon_api.get_financials_as_df('AAPL', 10, 'test-key', timeframe='quarterly')
+    df = polygon_api.create_financials_dataframe(data)
+
+    assert list(df['Fiscal Period']) == ['Q1', 'Q4']
+    assert pd.api.types.is_numeric_dtype(df['Revenues'])
+    assert pd.api.types.is_datetime64_any_dtype(df['End Date'])
+    assert df['Gross Margin (%)'].iloc[0] == pytest.approx(54855 / 119575 * 100)
+    # Operating cash flow less the cash invested, none in quarters where investing brought cash in
+    assert list(df['Free Cash Flow']) == [39895000000, 21598000000]
+    assert df['Debt to Equity'].iloc[0] == pytest.approx(95088 / 74100)
+
+
+def test_peer_metrics_use_the_latest_report_of_each_ticker():
+    table = polygon_api.get_peer_metrics_as_df(['AAPL', 'MSFT', 'NOPE'], 'quarterly', 'test-key')
+
+    assert list(table.index) == ['AAPL', 'MSFT']
+    assert list(table['Period']) == ['Q1 2024', 'Q2 2024']
+    assert table.loc['MSFT', 'Operating Margin (%)'] == pytest.approx(27032 / 62020 * 100)
+    assert pd.api.types.is_numeric_dtype(table['Revenue'])
+
+
+def test_stock_splits_adjustment_factor():
+    df = polygon_api.get_stock_splits('TSLA', 10, 'test-key')
+
+    assert list(df['Adjustment Factor']) == [1 / 3, 1 / 5]
+
+
+def test_news_returns_empty_list_on_error(mock_polygon):
+    mock_polygon.add_response('/v2/reference/news', 502, {'status': 'ERROR'})
+
+    assert polygon_api.get_news('test-key') == []
@@ -0,0 +1,321 @@
+import pytest
+import polygon_client
+from polygon_client import PolygonAPIError
+
+
+def test_get_aggregates_parses_bars(client):
+    bars = client.get_aggregates('AAPL', '2024-01-01', '2024-01-31')
+
+    assert len(bars) == 9
+    assert bars[0].open == 187.15
+    assert bars[0].close == 185.64
+    assert bars[0].vwap == 185.9465
+    assert bars[0].transactions == 1008871
+
+
+def test_get_aggregates_with_multiplier(client, mock_polygon):
+    client.get_aggregates('AAPL', '2024-01-02', '2024-01-03', timespan='minute', multiplier=5)
+
+    assert mock_polygon.request_paths() == ['/v2/aggs/ticker/AAPL/range/5/minute/2024-01-02/2024-01-03']
+
+
+def test_get_aggregates_latest_bars_first(client):
+    bars = client.get_aggregates('AAPL', '2024-01-01', '2024-01-31', sort='desc', limit=3)
+
+    assert [bar.close for bar in bars] == [185.92, 185.59, 186.19]
+
+
+def test_get_aggregates_empty_results(client):
+    assert client.get_aggregates('NODATA', '2024-01-01', '2024-01-31') == []
+
+
+def test_paginates_until_all_pages_are_read(client, mock_polygon, monkeypatch):
+    monkeypatch.setitem(polygon_client.MAX_PAGE_SIZE, 'dividends', 2)
+
+    dividends = client.get_dividends('AAPL', limit=50)
+
+    assert [d.ex_dividend_date for d in dividends] == ['2024-05-10', '2024-02-09', '2023-11-10', '2023-08-11', '2023-05-12']
+    assert mock_polygon.request_paths() == ['/v3/reference/dividends'] * 3
+
+
+def test_pagination_stops_at_record_budget(client, mock_polygon, monkeypatch):
+    monkeypatch.setitem(polygon_client.MAX_PAGE_SIZE, 'dividends', 2)
+
+    dividends = client.get_dividends('AAPL', limit=3)
+
+    assert len(dividends) == 3
+    assert len(mock_polygon.request_paths()) == 2
+
+
+def test_get_sma_with_window_and_dates(client, mock_polygon):
+    values = client.get_sma('AAPL', '2024-01-01', '2024-01-31', window=3)
+
+    assert mock_polygon.request_paths() == ['/v1/indicators/sma/AAPL']
+    query = mock_polygon.request_queries()[0]
+    assert (query['window'], query['timespan'], query['series_type'], query['adjusted']) == ('3', 'day', 'close', 'true')
+    assert (query['timestamp.gte'], query['timestamp.lte']) == ('2024-01-01', '2024-01-31')
+    # The first full window ends on the third bar
+    assert len(values) == 7
+    assert values[0].value == pytest.approx((185.64 + 184.25 + 181.91) / 3)
+    assert values[0].signal is None
+
+
+def test_indicator_values_are_paginated(client, mock_polygon, monkeypatch):
+    monkeypatch.setitem(polygon_client.MAX_PAGE_SIZE, 'indicators', 2)
+
+    values = client.get_rsi('AAPL', '2024-01-01', '2024-01-31', window=3)
+
+    assert len(values) == 6
+    assert all(0 <= value.value <= 100 for value in values)
+    assert values == sorted(values, key=lambda value: value.timestamp)
+    assert mock_polygon.request_paths() == ['/v1/indicators/rsi/AAPL'] * 3
+
+
+def test_get_macd_has_signal_and_histogram(client, mock_polygon):
+    values = client.get_macd('AAPL', '2024-01-01', '2024-01-31', short_window=2, long_window=3, signal_window=2)
+
+    assert mock_polygon.request_queries()[0]['long_window'] == '3'
+    assert len(values) == 7
+    assert values[-1].histogram == pytest.approx(values[-1].value - values[-1].signal)
+
+
+def test_get_stock_splits_with_date_filters(client):
+    splits = client.get_stock_splits('AAPL', limit=10, gte='2020-01-01', lt='')
+
+    assert [(s.execution_date, s.split_from, s.split_to) for s in splits] == [('2020-08-31', 1, 4)]
+
+
+def test_get_financials_keeps_statement_structure(client):
+    reports = client.get_financials('AAPL', limit=10, timeframe='quarterly')
+
+    assert [r.fiscal_period for r in reports] == ['Q1', 'Q4']
+    revenues = reports[0].financials['income_statement']['revenues']
+    assert revenues.label == 'Revenues'
+    assert revenues.value == 119575000000
+    assert revenues.unit == 'USD'
+
+
+def test_get_news_for_ticker(client):
+    news = client.get_news('TSLA', limit=5)
+
+    assert [article.title for article in news] == ['Tesla deliveries fall short of expectations']
+
+
+def test_get_previous_close(client):
+    bar = client.get_previous_close('AAPL')
+
+    assert bar.ticker == 'AAPL'
+    assert bar.close == 185.92
+
+
+def test_get_daily_open_close_with_extended_hours(client):
+    open_close = client.get_daily_open_close('AAPL', '2024-01-12')
+
+    assert (open_close.date, open_close.open, open_close.close) == ('2024-01-12', 186.06, 185.92)
+    assert (open_close.pre_market, open_close.after_hours) == (186.27, 185.85)
+
+
+def test_get_grouped_daily(client):
+    bars = client.get_grouped_daily('2024-01-12')
+
+    assert len(bars) == 12
+    assert bars[0].ticker == 'AAPL'
+    # Weekends and holidays have no bars
+    assert client.get_grouped_daily('2024-01-15') == []
+
+
+def test_get_snapshots_for_watchlist(client, mock_polygon):
+    snapshots = client.get_snapshots(['AAPL', 'TSLA'])
+
+    assert [snapshot.ticker for snapshot in snapshots] == ['AAPL', 'TSLA']
+    assert mock_polygon.request_queries()[0]['tickers'] == 'AAPL,TSLA'
+    aapl = snapshots[0]
+    assert (aapl.last_trade_price, aapl.bid, aapl.ask, aapl.prev_close) == (185.92, 185.91, 185.93, 185.59)
+    assert aapl.todays_change_percent == 0.1778
+
+
+def test_get_single_snapshot(client):
+    assert client.get_snapshot('MSFT').day_close == 388.47
+
+
+def test_get_market_movers(client):
+    losers = client.get_market_movers('losers')
+
+    assert losers[0].ticker == 'TSLA'
+    assert all(snapshot.todays_change_percent < 0 for snapshot in losers)
+    with pytest.raises(ValueError):
+        client.get_market_movers('sideways')
+
+
+def test_get_forex_and_crypto_snapshots(client, mock_polygon):
+    eurusd = client.get_snapshots(['C:EURUSD'], market='fx')[0]
+    crypto = client.get_snapshots(market='crypto')
+
+    assert mock_polygon.request_paths()[0] == '/v2/snapshot/locale/global/markets/forex/tickers'
+    # Forex quotes come as a/b
+    assert (eurusd.bid, eurusd.ask) == (1.0949, 1.0951)
+    assert [snapshot.ticker for snapshot in crypto] == ['X:BTCUSD', 'X:ETHUSD', 'X:SOLUSD']
+    assert crypto[0].last_trade_price == 42830.5
+    assert client.get_snapshot('X:ETHUSD').day_close == 2520.1
+
+
+def test_get_crypto_movers_and_grouped_daily(client):
+    assert [snapshot.ticker for snapshot in client.get_market_movers('gainers', market='crypto')] == ['X:SOLUSD']
+    bars = client.get_grouped_daily('2024-01-13', market='crypto')
+
+    assert {bar.ticker for bar in bars} == {'X:BTCUSD', 'X:ETHUSD', 'X:SOLUSD', 'X:DOGEUSD'}
+    with pytest.raises(ValueError):
+        client.get_grouped_daily('2024-01-13', market='bonds')
+
+
+def test_crypto_aggregates_are_not_split_adjusted(client, mock_polygon):
+    bars = client.get_aggregates('X:BTCUSD', '2024-01-01', '2024-01-31', adjusted=True)
+
+    assert len(bars) == 31
+    assert mock_polygon.request_queries()[0]['adjusted'] == 'false'
+
+
+def test_get_index_snapshots(client, mock_polygon):
+    snapshots = client.get_snapshots(['I:SPX', 'I:VIX', 'I:NOPE'], market='indices')
+
+    assert mock_polygon.request_paths()[0] == '/v3/snapshot/indices'
+    assert mock_polygon.request_queries()[0]['ticker.any_of'] == 'I:SPX,I:VIX,I:NOPE'
+    # Unknown tickers come back as error items and are left out
+    assert [snapshot.ticker for snapshot in snapshots] == ['I:SPX', 'I:VIX']
+    assert snapshots[0].last_trade_price == 4783.83
+    assert snapshots[0].prev_close == 4780.24
+    assert snapshots[0].day_volume is None
+    assert client.get_snapshot('I:DJI').last_trade_price == 37592.98
+    with pytest.raises(PolygonAPIError):
+        client.get_snapshot('I:NOPE')
+
+
+def test_indices_have_no_movers_or_grouped_daily(client):
+    with pytest.raises(ValueError):
+        client.get_market_movers('gainers', market='indices')
+    with pytest.raises(ValueError):
+        client.get_grouped_daily('2024-01-12', market='indices')
+
+
+def test_index_aggregates_have_no_volume(client):
+    bars = client.get_aggregates('I:SPX', '2024-01-01', '2024-01-31')
+
+    assert len(bars) == 9
+    assert bars[-1].close == 4783.83
+    assert bars[-1].volume is None
+
+
+def test_get_currency_conversion(client):
+    conversion = client.get_currency_conversion('USD', 'EUR', amount=100)
+
+    assert (conversion.from_currency, conversion.to_currency) == ('USD', 'EUR')
+    assert conversion.converted == pytest.approx(100 / 1.095, abs=1e-4)
+    assert (conversion.bid, conversion.ask) == (1.0949, 1.0951)
+
+
+def test_get_trades_in_time_window(client, mock_polygon):
+    # 2024-01-02 09:30 to 09:31 US/Eastern in Unix nanoseconds
+    trades = client.get_trades('AAPL', 1704205800000000000, 1704205860000000000)
+
+    assert len(trades) == 48
+    assert (trades[0].price, trades[0].size, trades[0].exchange, trades[0].conditions) == (187.16, 500, 21, [16, 17])
+    assert mock_polygon.request_queries()[0]['timestamp.gte'] == '1704205800000000000'
+
+
+def test_get_quotes(client):
+    quotes = client.get_quotes('AAPL', 1704205800000000000, 1704205860000000000, limit=5)
+
+    assert len(quotes) == 5
+    assert all(quote.ask_price > quote.bid_price for quote in quotes)
+
+
+def test_get_exchanges_of_asset_class(client):
+    exchanges = client.get_exchanges('stocks')
+
+    assert {exchange.asset_class for exchange in exchanges} == {'stocks'}
+    assert next(exchange for exchange in exchanges if exchange.id == 10).mic == 'XNYS'
+    assert [exchange.name for exchange in client.get_exchanges('crypto')] == ['Coinbase', 'Bitfinex']
+
+
+def test_get_trade_conditions(client):
+    conditions = client.get_conditions('stocks', 'trade')
+
+    assert {condition.id: condition.name for condition in conditions}[14] == 'Intermarket Sweep'
+    assert all('trade' in condition.data_types for condition in conditions)
+
+
+def test_get_market_status(client):
+    status = client.get_market_status()
+
+    assert status.market == 'open'
+    assert status.exchanges['nyse'] == 'open'
+    assert status.early_hours is False
+
+
+def test_get_upcoming_holidays(client):
+    holidays = client.get_upcoming_holidays()
+
+    early_close = next(holiday for holiday in holidays if holiday.status == 'early-close')
+    assert (early_close.date, early_close.close) == ('2025-07-03', '2025-07-03T17:00:00.000Z')
+
+
+def test_search_tickers_by_name(client, mock_polygon):
+    tickers = client.search_tickers('apple')
+
+    assert [ticker.ticker for ticker in tickers] == ['AAPL']
+    assert mock_polygon.request_queries()[0]['search'] == 'apple'
+
+
+def test_search_tickers_with_filters(client):
+    assert [t.ticker for t in client.search_tickers(ticker_type='ETF')] == ['QQQ', 'SPY']
+    assert [t.ticker for t in client.search_tickers(exchange='XNYS', active=False)] == ['TWTR']
+
+
+def test_get_options_contracts_of_expiration(client, mock_polygon):
+    contracts = client.get_options_contracts('AAPL', '2024-01-19', 'call', gte=185)
+
+    assert [contract.ticker for contract in contracts] == ['O:AAPL240119C00185000', 'O:AAPL240119C00190000', 'O:AAPL240119C00195000']
+    assert contracts[0].shares_per_contract == 100
+    assert mock_polygon.request_queries()[0]['strike_price.gte'] == '185'
+
+
+def test_get_option_chain_with_missing_greeks(client):
+    chain = {option.ticker: option for option in client.get_option_chain('AAPL', '2024-01-19')}
+
+    assert len(chain) == 10
+    at_the_money = chain['O:AAPL240119C00185000']
+    assert at_the_money.underlying_price == 185.92
+    assert at_the_money.midpoint == pytest.approx((at_the_money.bid + at_the_money.ask) / 2)
+    assert 0.5 < at_the_money.delta < 0.7
+    assert chain['O:AAPL240119P00175000'].implied_volatility is None
+    assert chain['O:AAPL240119P00175000'].delta is None
+
+
+def test_get_ticker_details(client):
+    details = client.get_ticker_details('AAPL')
+
+    assert details.name == 'Apple Inc.'
+    assert details.address['city'] == 'CUPERTINO'
+
+
+def test_get_related_companies(client):
+    assert client.get_related_companies('AAPL') == ['MSFT', 'GOOGL', 'AMZN']
+
+
+def test_unknown_ticker_details_raise_api_error(client):
+    with pytest.raises(PolygonAPIError) as error:
+        client.get_ticker_details('NOPE')
+    assert error.value.status_code == 404
+
+
+def test_server_errors_raise_api_error(client, mock_polygon):
+    mock_polygon.add_response('/v2/reference/news', 500, {'status': 'ERROR', 'error': 'Internal error'})
+
+    with pytest.raises(PolygonAPIError) as error:
+        client.get_news()
+    assert error.value.status_code == 500
+
+
+def test_requires_api_key():
+    with pytest.raises(ValueError):
+        polygon_client.PolygonClient('')
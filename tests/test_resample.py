@@ -0,0 +1,83 @@
+import pandas as pd
+import pytest
+from resample import filter_session, resample_bars
+
+
+# Minute bars in US/Eastern with the given start times (HH:MM on 2024-01-02) and rising prices
+def make_bars(times, day='2024-01-02'):
+    index = pd.DatetimeIndex([pd.Timestamp(f'{day} {t}', tz='US/Eastern') for t in times], name='Date')
+    prices = pd.Series(range(1, len(times) + 1), dtype='float64').values
+    return pd.DataFrame({'Open': prices, 'High': prices + 0.5, 'Low': prices - 0.5, 'Close': prices + 0.25,
+                         'Volume': 100, 'VWAP': prices, 'Transactions': pd.array([10] * len(times), dtype='Int64')}, index=index)
+
+
+def test_regular_session_drops_pre_and_after_market():
+    bars = make_bars(['09:29', '09:30', '15:59', '16:00'])
+
+    assert [t.strftime('%H:%M') for t in filter_session(bars, 'regular').index] == ['09:30', '15:59']
+    assert len(filter_session(bars, 'extended')) == 4
+
+
+def test_unknown_session():
+    with pytest.raises(ValueError):
+        filter_session(make_bars(['09:30']), 'overnight')
+
+
+def test_resample_aggregates_ohlcv():
+    bars = make_bars(['09:30', '09:31', '09:32', '09:35'])
+    bars.loc[bars.index[2], 'Volume'] = 200
+
+    result = resample_bars(bars, '5min')
+
+    assert [t.strftime('%H:%M') for t in result.index] == ['09:30', '09:35']
+    first = result.iloc[0]
+    assert (first['Open'], first['High'], first['Low'], first['Close']) == (1.0, 3.5, 0.5, 3.25)
+    assert first['Volume'] == 400
+    assert first['VWAP'] == pytest.approx((1 * 100 + 2 * 100 + 3 * 200) / 400)
+    assert first['Transactions'] == 30
+    assert result['Volume'].dtype == 'int64'
+
+
+def test_resample_skips_intervals_without_trades():
+    bars = make_bars(['09:30', '11:00'])
+
+    assert len(resample_bars(bars, '15min')) == 2
+
+
+def test_weekly_bars_are_labelled_with_monday():
+    bars = pd.concat([make_bars(['10:00'], '2024-01-02'), make_bars(['10:00'], '2024-01-05'), make_bars(['10:00'], '2024-01-08')])
+
+    result = resample_bars(bars, 'W-MON')
+
+    assert [t.strftime('%Y-%m-%d') for t in result.index] == ['2024-01-01', '2024-01-08']
+    assert list(result['Volume']) == [200, 100]
+
+
+def test_resample_index_bars_without_volume():
+    bars = make_bars(['09:30', '09:31']).drop(columns=['Volume', 'VWAP', 'Transactions'])
+
+    result = resample_bars(bars, '5min')
+
+    assert list(result.columns) == ['Open', 'High', 'Low', 'Close']
+    assert result.iloc[0]['Close'] == 2.25
+
+
+def test_resample_keeps_fractional_volume():
+    bars = make_bars(['09:30', '09:31'])
+    bars['Volume'] = [0.25, 0.5]
+
+    assert list(resample_bars(bars, '5min')['Volume']) == [0.75]
+
+
+def test_crypto_days_are_resampled_in_utc():
+    # 18:59 and 19:00 in New York are either side of midnight UTC
+    bars = make_bars(['18:59', '19:00', '19:01'])
+    bars['Volume'] = 0.5
+
+    result = resample_bars(bars, '1D', day_timezone='UTC')
+
+    assert list(result.index) == [pd.Timestamp('2024-01-02', tz='UTC'), pd.Timestamp('2024-01-03', tz='UTC')]
+    assert list(result['Open']) == [1.0, 2.0]
+    assert list(result['Volume']) == [0.5, 1.0]
+    # Stock days stay in US/Eastern
+    assert len(resample_bars(bars, '1D')) == 1
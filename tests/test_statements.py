@@ -0,0 +1,43 @@
+import pytest
+from mock_polygon import load_fixture
+from models import FinancialReport
+from statements import statement_table
+
+
+@pytest.fixture
+def reports():
+    return [FinancialReport.from_api(item) for item in load_fixture('financials')['results'] if 'AAPL' in item['tickers']]
+
+
+def test_report_keeps_statement_structure(reports):
+    report = reports[0]
+
+    assert report.period_label == 'Q1 2024'
+    assert [key for key, _ in report.statement('balance_sheet')][:3] == ['assets', 'current_assets', 'inventory']
+    assert dict(report.statement('income_statement'))['diluted_earnings_per_share'].unit == 'USD / shares'
+
+
+def test_statement_table_has_periods_as_columns(reports):
+    quarters = [report for report in reports if report.timeframe == 'quarterly']
+    table = statement_table(quarters, 'income_statement')
+
+    assert list(table.columns) == ['Unit', 'Q1 2024', 'Q4 2023']
+    assert list(table.index[:3]) == ['Revenues', 'Cost Of Revenue', 'Gross Profit']
+    assert table.loc['Revenues', 'Q4 2023'] == 89498000000
+    assert table.loc['Diluted Earnings Per Share', 'Unit'] == 'USD / shares'
+
+
+def test_collapsed_statement_shows_totals_only(reports):
+    table = statement_table(reports, 'cash_flow_statement', detailed=False)
+
+    assert list(table.index) == ['Net Cash Flow From Operating Activities', 'Net Cash Flow From Investing Activities',
+                                 'Net Cash Flow From Financing Activities', 'Net Cash Flow']
+    # The annual and fourth-quarter reports end on the same day and are both kept
+    assert list(table.columns) == ['Unit', 'Q1 2024', 'FY 2023', 'Q4 2023']
+
+
+def test_trailing_reports_are_named_by_their_end_date():
+    report = FinancialReport.from_api({'timeframe': 'ttm', 'fiscal_period': 'TTM', 'end_date': '2023-12-30'})
+
+    assert report.period_label == 'TTM 2023-12-30'
+    assert statement_table([report], 'income_statement').empty
@@ -0,0 +1,124 @@
+import json
+import time
+import pytest
+from models import Aggregate, StreamTrade
+from replay_polygon import ReplayServer
+from streaming import BarBuffer, PolygonStream, stream_symbol
+
+
+# Poll a condition until it holds or the timeout expires
+def wait_for(condition, timeout=5):
+    deadline = time.monotonic() + timeout
+    while time.monotonic() < deadline:
+        if condition():
+            return True
+        time.sleep(0.05)
+    return condition()
+
+
+def test_aggregates_replace_bars_with_the_same_start():
+    buffer = BarBuffer(max_bars=2)
+    buffer.add_aggregate(Aggregate(60000, 10, 11, 9, 10.5, volume=100, ticker='AAPL'))
+    buffer.add_aggregate(Aggregate(60000, 10, 12, 9, 11.5, volume=150, ticker='AAPL'))
+    buffer.add_aggregate(Aggregate(120000, 11.5, 12, 11, 11.8, volume=80, ticker='AAPL'))
+    # A late bar goes in order, then the oldest bar leaves the window
+    buffer.add_aggregate(Aggregate(0, 9, 10, 9, 10, volume=50, ticker='AAPL'))
+
+    df = buffer.to_frame('AAPL')
+
+    assert [timestamp.value // 10 ** 6 for timestamp in df.index] == [60000, 120000]
+    assert df['Close'].iloc[0] == 11.5
+    assert str(df.index.tz) == 'US/Eastern'
+
+
+def test_trades_are_rolled_up_into_bars():
+    buffer = BarBuffer(interval=5, from_trades=True)
+    for timestamp, price, size in [(1000, 10.0, 100), (2000, 10.4, 50), (4999, 10.1, 50), (5000, 10.2, 10)]:
+        buffer.add_trade(StreamTrade('AAPL', timestamp, price, size))
+
+    df = buffer.to_frame('AAPL')
+
+    assert len(df) == 2
+    first = df.iloc[0]
+    assert (first['Open'], first['High'], first['Low'], first['Close'], first['Volume']) == (10.0, 10.4, 10.0, 10.1, 200)
+    assert first['VWAP'] == pytest.approx((10.0 * 100 + 10.4 * 50 + 10.1 * 50) / 200)
+    assert first['Transactions'] == 3
+    assert buffer.last_trades['AAPL'].price == 10.2
+
+
+def test_crypto_events_use_prefixed_tickers():
+    stream = PolygonStream('test-key', ['X:BTCUSD'], ('minute', 'trades', 'quotes'), market='crypto')
+    stream.handle_message(json.dumps([
+        {'ev': 'XA', 'pair': 'BTC-USD', 'o': 42830.5, 'h': 42850.0, 'l': 42825.5, 'c': 42843.0, 'v': 1.2345, 'vw': 42836.75, 's': 1705104000000, 'e': 1705104060000},
+        {'ev': 'XQ', 'pair': 'BTC-USD', 'bp': 42842.5, 'bs': 0.8, 'ap': 42843.5, 'as': 1.1, 't': 1705104059000, 'x': 1},
+        {'ev': 'status', 'status': 'success', 'message': 'subscribed to: XA.BTC-USD'},
+    ]))
+
+    assert stream.subscription() == 'XA.BTC-USD,XT.BTC-USD,XQ.BTC-USD'
+    assert stream.buffer.to_frame('X:BTCUSD')['Volume'].iloc[0] == 1.2345
+    assert stream.buffer.last_quotes['X:BTCUSD'].ask_price == 42843.5
+    assert stream.events == 2
+    assert stream_symbol('C:EURUSD', 'fx') == 'EUR/USD'
+
+
+def test_malformed_messages_are_skipped():
+    stream = PolygonStream('test-key', ['AAPL'], ('minute', 'trades'))
+    stream.handle_message('{"ev": "AM"')
+    stream.handle_message(json.dumps({'ev': 'AM'}))
+    stream.handle_message(json.dumps([
+        'AM',
+        {'ev': 'AM', 'o': 185.0, 'h': 185.5, 'l': 184.9, 'c': 185.2, 's': 1704205800000},
+        {'ev': 'T', 'sym': 'AAPL', 'p': 185.2},
+        {'ev': 'AM', 'sym': 'AAPL', 'o': 185.0, 'h': 185.5, 'l': 184.9, 'c': 185.2, 'v': 1200, 's': 1704205800000},
+    ]))
+
+    # Only the well-formed aggregate gets through
+    assert stream.events == 1
+    assert len(stream.buffer.to_frame('AAPL')) == 1
+    assert stream.buffer.last_trades == {}
+
+
+def test_unsupported_channels():
+    with pytest.raises(ValueError):
+        PolygonStream('test-key', ['C:EURUSD'], ('trades',), market='fx')
+    with pytest.raises(ValueError):
+        PolygonStream('test-key', ['AAPL'], ('quotes',))
+
+
+def test_stream_from_replay_server(replay_polygon):
+    stream = PolygonStream('test-key', ['AAPL'], ('minute', 'trades', 'quotes'), url=f"{replay_polygon.base_url}/stocks").start()
+    try:
+        assert wait_for(lambda: len(stream.buffer.to_frame('AAPL')) == 10)
+        assert stream.status == 'streaming'
+    finally:
+        stream.stop()
+
+    df = stream.buffer.to_frame('AAPL')
+    # Only the subscribed ticker is replayed
+    assert list(stream.buffer.bars) == ['AAPL']
+    assert df.index[0].strftime('%Y-%m-%d %H:%M') == '2024-01-02 09:30'
+    assert stream.buffer.last_trades['AAPL'].price == df['Close'].iloc[-1]
+    assert stream.buffer.last_quotes['AAPL'].bid_price < stream.buffer.last_quotes['AAPL'].ask_price
+    assert not stream.running
+
+
+def test_malformed_frame_does_not_stop_the_stream():
+    with ReplayServer(frames=['not json', json.dumps([{'ev': 'AM', 'o': 185.0}])]) as server:
+        stream = PolygonStream('test-key', ['AAPL'], url=f"{server.base_url}/stocks").start()
+        try:
+            assert wait_for(lambda: len(stream.buffer.to_frame('AAPL')) == 10)
+            assert stream.status == 'streaming'
+            assert stream.running
+        finally:
+            stream.stop()
+    assert server.connections == 1
+
+
+def test_rejected_api_key_stops_the_stream():
+    with ReplayServer(api_key='secret') as server:
+        stream = PolygonStream('wrong-key', ['AAPL'], url=f"{server.base_url}/stocks").start()
+
+        assert wait_for(lambda: not stream.running)
+    assert stream.status == 'error'
+    assert 'authentication failed' in stream.error
+    assert server.connections == 1
@@ -0,0 +1,105 @@
+import math
+import pandas as pd
+import pytest
+from mock_polygon import load_fixture
+from models import FinancialReport, Dividend
+from valuation import valuation_multiples, current_multiples, dcf_defaults, discounted_cash_flow, dcf_sensitivity, rate_range
+
+
+def make_report(end_date, **values):
+    return FinancialReport.from_api({
+        'end_date': end_date, 'timeframe': 'annual', 'fiscal_period': 'FY', 'fiscal_year': end_date[:4],
+        'financials': {'income_statement': {key: {'value': value} for key, value in values.items()}},
+    })
+
+
+def make_closes(closes):
+    return pd.Series(list(closes.values()), index=pd.DatetimeIndex(list(closes), tz='US/Eastern', name='Date'), name='Close')
+
+
+@pytest.fixture
+def report():
+    return make_report('2023-09-30', revenues=1000.0, net_income_loss=100.0, diluted_earnings_per_share=2.0, equity=500.0,
+                       operating_income_loss=150.0, depreciation_and_amortization=50.0, long_term_debt=300.0, cash=100.0,
+                       net_cash_flow_from_operating_activities=120.0, net_cash_flow_from_investing_activities=-20.0)
+
+
+@pytest.fixture
+def dividends():
+    return [
+        Dividend('AAPL', 0.5, ex_dividend_date='2023-08-10', dividend_type='CD'),
+        Dividend('AAPL', 1.0, ex_dividend_date='2023-06-01', dividend_type='SC'),
+        Dividend('AAPL', 0.5, ex_dividend_date='2022-11-10', dividend_type='CD'),
+        Dividend('AAPL', 0.5, ex_dividend_date='2022-08-10', dividend_type='CD'),
+    ]
+
+
+def test_multiples_at_the_end_of_each_report(report, dividends):
+    # The fiscal year ends on a Saturday, so it is priced at Friday's close
+    multiples = valuation_multiples([report], make_closes({'2023-09-28': 39.0, '2023-09-29': 40.0}), dividends).iloc[0]
+
+    assert multiples['Price'] == 40.0
+    assert multiples['Market Cap'] == 2000.0  # 50 shares implied from net income over EPS
+    assert multiples['P/E'] == 20.0
+    assert multiples['P/S'] == 2.0
+    assert multiples['P/B'] == 4.0
+    assert multiples['EV/EBITDA'] == pytest.approx((2000 + 300 - 100) / 200)
+    assert multiples['FCF Yield (%)'] == pytest.approx(5.0)
+    # Regular dividends of the trailing year only
+    assert multiples['Dividend Yield (%)'] == pytest.approx(2.5)
+
+
+def test_stale_prices_and_losses_leave_multiples_out(report):
+    loss = make_report('2022-09-30', revenues=900.0, net_income_loss=-10.0, diluted_earnings_per_share=-0.2, equity=450.0)
+    multiples = valuation_multiples([report, loss], make_closes({'2022-09-30': 30.0, '2023-09-01': 40.0}))
+
+    assert list(multiples['Period']) == ['FY 2022', 'FY 2023']
+    assert math.isnan(multiples['P/E'].iloc[0])
+    assert math.isnan(multiples['Price'].iloc[1])
+
+
+def test_current_multiples_use_the_latest_close(report, dividends):
+    current = current_multiples([report], make_closes({'2023-09-29': 40.0, '2024-01-12': 50.0}), dividends)
+
+    assert current['Period'] == 'FY 2023'
+    assert current['P/E'] == 25.0
+    # Only the August dividend falls in the year to January 12
+    assert current['Dividend Yield (%)'] == pytest.approx(1.0)
+
+
+def test_perpetuity_values_match_the_closed_form():
+    projection, summary = discounted_cash_flow(100.0, 0.0, 0.1, 0.1, 0.0, years=1, net_debt=20.0, shares=8.0)
+
+    assert projection.loc[1, 'Present Value'] == pytest.approx(10 / 1.1)
+    # A flat cash flow of 10 discounted at 10% is worth 100
+    assert summary['Enterprise Value'] == pytest.approx(100.0)
+    assert summary['Value per Share'] == pytest.approx(10.0)
+    with pytest.raises(ValueError):
+        discounted_cash_flow(100.0, 0.0, 0.1, 0.03, 0.03)
+
+
+def test_sensitivity_table():
+    table = dcf_sensitivity(100.0, 0.05, 0.2, rate_range(0.09, 0.01), rate_range(0.07, 0.01, count=3), shares=10.0)
+
+    assert list(table.index) == [0.07, 0.08, 0.09, 0.1, 0.11]
+    assert list(table.columns) == [0.06, 0.07, 0.08]
+    assert math.isnan(table.loc[0.07, 0.07]) and math.isnan(table.loc[0.07, 0.08])
+    # Higher discount rates lower the value
+    assert table[0.06].is_monotonic_decreasing
+
+
+def test_dcf_defaults_from_the_recorded_filings():
+    reports = [FinancialReport.from_api(item) for item in load_fixture('financials')['results'] if 'AAPL' in item['tickers'] and item['timeframe'] == 'annual']
+    defaults = dcf_defaults(reports)
+
+    assert defaults['revenue'] == 383285000000
+    assert defaults['shares'] == pytest.approx(96995000000 / 6.13)
+    assert defaults['fcf_margin'] == pytest.approx(110543 / 383285)
+    assert defaults['estimated'] == ['growth', 'net_debt']
+
+
+def test_dcf_defaults_fall_back_when_the_filings_lack_inputs():
+    defaults = dcf_defaults([make_report('2023-09-30', revenues=1000.0, net_income_loss=100.0, diluted_earnings_per_share=2.0)])
+
+    assert defaults['estimated'] == ['growth', 'fcf_margin', 'net_debt']
+    assert defaults['fcf_margin'] == pytest.approx(0.1)